@@ -6,6 +6,7 @@ extern crate serde;
 #[path = "../../maps.rs"]
 mod maps;
 use engine::inputs::Keycodes as Keycode;
+use engine::inputs::Modifiers;
 use engine::traits::*;
 fn sprite_frame(game: &mut engine::Engine, spr: &Option<engine::Sprite>) {
     if let Some(spr) = spr {
@@ -25,20 +26,17 @@ fn sprite_frame(game: &mut engine::Engine, spr: &Option<engine::Sprite>) {
 
 async fn init() {
     let game = engine::EngineWrapper::new("FPS Map Editor".to_owned(), (600, 600, 1)).await;
-    let args: Vec<_> = std::env::args().collect();
-    //let mut c_world = WorldConstructor::new();
-    let mut c_world: maps::WorldConstructor;
-    if args.len() > 1 {
-        c_world = maps::WorldConstructor::load_file(args[1].clone()).unwrap();
-    } else {
-        panic!("Filename required");
-    }
+    // The map can be given as an argument or dropped on the window
+    let mut map_path: Option<String> = std::env::args().nth(1);
+    let mut c_world = match &map_path {
+        Some(path) => load_world(path).unwrap(),
+        None => maps::WorldConstructor::new(),
+    };
     let mut typing = false;
     let mut typed_string = String::new();
     let mut finished_string = String::new();
-    std::fs::write(&args[1], ron::ser::to_string(&c_world.to_world()).unwrap()).unwrap();
-    for tile in &mut c_world.tiles {
-        tile.1.load().unwrap();
+    if let Some(path) = &map_path {
+        std::fs::write(path, ron::ser::to_string(&c_world.to_world()).unwrap()).unwrap();
     }
     let selected_tile: Option<&maps::Tile> = None;
     let mut selected_tile_index = 0;
@@ -53,6 +51,8 @@ async fn init() {
     let mut add_tile_field = 0;
 
     // MAP EDIT VARS
+    // A message shown at the top of the window for a few seconds
+    let mut status: Option<(String, f64)> = None;
     let mut c_tile_x: f64 = 0.0;
     let mut c_tile_y: f64 = 0.0;
     // END
@@ -60,20 +60,37 @@ async fn init() {
         if game.get_key(Keycode::Escape).any() {
             return Ok(false);
         }
-        if typing {
-            let keys = game.get_pressed();
-            for key in &keys {
-                if *key == Keycode::Return || *key == Keycode::NumpadEnter {
-                    typing = false;
-                    finished_string = typed_string.clone();
-                    typed_string = String::new();
-                } else if *key == Keycode::Return {
-                    typed_string.pop();
-                } else {
-                    typed_string += &normalize(*key);
+        for path in game.get_dropped_files() {
+            let is_png = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+            if is_png {
+                // A dropped image is the sprite of the tile being added
+                if add_tile && add_tile_field == 0 {
+                    typed_string = path.display().to_string();
+                }
+            } else {
+                match load_world(&path.display().to_string()) {
+                    Ok(world) => {
+                        c_world = world;
+                        map_path = Some(path.display().to_string());
+                        selected_tile_index = 0;
+                    }
+                    Err(e) => eprintln!("Could not load {}: {}", path.display(), e),
                 }
             }
         }
+        if typing {
+            typed_string += game.get_text_input();
+            if game.get_key(Keycode::Back).pressed {
+                typed_string.pop();
+            }
+            if game.get_key(Keycode::Return).pressed || game.get_key(Keycode::NumpadEnter).pressed {
+                typing = false;
+                finished_string = typed_string.clone();
+                typed_string = String::new();
+            }
+        }
         if !typing && !add_tile && game.get_key(Keycode::P).pressed {
             selected_tile_index += 1;
             if selected_tile_index > c_world.tiles.len() {
@@ -185,12 +202,18 @@ async fn init() {
         }
 
         // END
-        if game.get_key(Keycode::S).pressed {
-            std::fs::write(
-                &args[1],
-                ron::ser::to_string(&c_world.to_world()).map_err(|e| e.to_string())?,
-            )
-            .map_err(|e| e.to_string())?;
+        if game.get_chord(Modifiers::CTRL, Keycode::S).pressed {
+            let message = if let Some(path) = &map_path {
+                std::fs::write(
+                    path,
+                    ron::ser::to_string(&c_world.to_world()).map_err(|e| e.to_string())?,
+                )
+                .map_err(|e| e.to_string())?;
+                format!("Saved to {}", path)
+            } else {
+                "Not saved: drop a map file first".to_owned()
+            };
+            status = Some((message, 3.0));
         }
 
         // FIXME: THIS WHOLE BLOCK !
@@ -373,10 +396,31 @@ async fn init() {
             },
             &format!("{:?}", add_tile),
         );
+        if let Some((message, time_left)) = &mut status {
+            game.draw_text((200, 10), 1, engine::Color::YELLOW, message);
+            *time_left -= game.elapsed;
+            if *time_left <= 0.0 {
+                status = None;
+            }
+        }
+        if map_path.is_none() {
+            game.draw_text((200, 0), 1, engine::Color::YELLOW, "Drop a map file here");
+        }
+        if !game.get_hovered_files().is_empty() {
+            game.draw_rect((0, 0), (599, 599), engine::Color::YELLOW);
+        }
         Ok(true)
     });
 }
 
+fn load_world(path: &str) -> Result<maps::WorldConstructor, String> {
+    let mut world = maps::WorldConstructor::load_file(path.to_owned())?;
+    for tile in world.tiles.values_mut() {
+        tile.load()?;
+    }
+    Ok(world)
+}
+
 fn str_normalize(source: String) -> String {
     let mut res: String = String::new();
     for chr in source.chars() {
@@ -387,50 +431,6 @@ fn str_normalize(source: String) -> String {
     res
 }
 
-fn normalize(source: Keycode) -> String {
-    use Keycode::*;
-    (match source {
-        A => "a",
-        B => "b",
-        C => "c",
-        D => "d",
-        E => "e",
-        F => "f",
-        G => "g",
-        H => "h",
-        I => "i",
-        J => "j",
-        K => "k",
-        L => "l",
-        M => "m",
-        N => "n",
-        O => "o",
-        P => "p",
-        Q => "q",
-        R => "r",
-        S => "s",
-        T => "t",
-        U => "u",
-        V => "v",
-        W => "w",
-        X => "x",
-        Y => "y",
-        Z => "z",
-        Key1 | Numpad1 => "1",
-        Key2 | Numpad2 => "2",
-        Key3 | Numpad3 => "3",
-        Key4 | Numpad4 => "4",
-        Key5 | Numpad5 => "5",
-        Key6 | Numpad6 => "6",
-        Key7 | Numpad7 => "7",
-        Key8 | Numpad8 => "8",
-        Key9 | Numpad9 => "9",
-        Key0 | Numpad0 => "0",
-        Space => " ",
-        _ => "",
-    })
-    .to_owned()
-}
 fn main() {
     #[cfg(target_arch = "wasm32")]
     {
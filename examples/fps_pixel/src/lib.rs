@@ -1,5 +1,6 @@
 extern crate pixel_engine as engine;
-use engine::inputs::Keycodes as Keycode;
+use engine::actions::{ActionMap, Binding};
+use engine::inputs::{CursorGrab, Keycodes as Keycode, MouseBtn};
 use engine::traits::*;
 use engine::*;
 use std::f64::consts::PI;
@@ -11,6 +12,9 @@ extern crate wasm_bindgen;
 use wasm_bindgen::prelude::*;
 
 pub mod maps;
+
+/// Radians turned per unit of raw mouse motion
+const MOUSE_SENSITIVITY: f64 = 0.003;
 struct Player {
     angle: f64,
     x: f64,
@@ -53,7 +57,23 @@ async fn init() {
     );
 
     let fac = 5;
-    let game = EngineWrapper::new("Pixel FPS".to_owned(), (120 * fac, 60 * fac, 10 / fac)).await;
+    let mut game =
+        EngineWrapper::new("Pixel FPS".to_owned(), (120 * fac, 60 * fac, 10 / fac)).await;
+    // ZQSD (AZERTY) and the arrows by default, rebind them in controls.ron
+    let controls = ActionMap::new()
+        .bind("turn_left", Binding::Key(Keycode::Q))
+        .bind("turn_left", Binding::Key(Keycode::Left))
+        .bind("turn_right", Binding::Key(Keycode::D))
+        .bind("turn_right", Binding::Key(Keycode::Right))
+        .bind("forward", Binding::Key(Keycode::Z))
+        .bind("forward", Binding::Key(Keycode::Up))
+        .bind("backward", Binding::Key(Keycode::S))
+        .bind("backward", Binding::Key(Keycode::Down))
+        .bind("strafe_left", Binding::Key(Keycode::A))
+        .bind("strafe_right", Binding::Key(Keycode::E));
+    #[cfg(not(target_arch = "wasm32"))]
+    let controls = ActionMap::load_from_file("controls.ron").unwrap_or(controls);
+    game.set_actions(controls);
     // =======================
     let viewport = (game.size.0, 7 * game.size.1 / 8);
     let mut player = Player::new();
@@ -73,20 +93,33 @@ async fn init() {
     #[cfg(target_arch = "wasm32")]
     map.load_all(spr_data).unwrap();
     let mut current_tile: char = '#';
+    let mut mouselook = false;
 
     // =======================
     game.run(move |game: &mut Engine| {
         game.clear(Color::BLACK);
         // WRITE YOUR CODE HERE
-        if game.get_key(Keycode::Q).held {
+        // Clicking captures the mouse to look around, Tab gives it back
+        if game.get_mouse_btn(MouseBtn::Left).pressed && !mouselook {
+            mouselook = game.set_cursor_grab(CursorGrab::Locked).is_ok();
+            game.set_cursor_visible(!mouselook);
+        } else if game.get_key(Keycode::Tab).pressed && mouselook {
+            mouselook = false;
+            game.set_cursor_grab(CursorGrab::None)?;
+            game.set_cursor_visible(true);
+        }
+        if mouselook {
+            player.angle += f64::from(game.get_mouse_raw_delta().0) * MOUSE_SENSITIVITY;
+        }
+        if game.get_action("turn_left").held {
             // TURN TO THE LEFT
             player.angle -= (player.speed * 0.75_f64) * game.elapsed;
         }
-        if game.get_key(Keycode::D).held {
+        if game.get_action("turn_right").held {
             // TURN TO THE RIGHT
             player.angle += (player.speed * 0.75_f64) * game.elapsed;
         }
-        if game.get_key(Keycode::Z).held {
+        if game.get_action("forward").held {
             // MOVE FORWARD
             player.x += player.angle.sin() * player.speed * game.elapsed;
             player.y += player.angle.cos() * player.speed * game.elapsed;
@@ -95,7 +128,7 @@ async fn init() {
                 player.y -= player.angle.cos() * player.speed * game.elapsed;
             }
         }
-        if game.get_key(Keycode::S).held {
+        if game.get_action("backward").held {
             // MOCE BACKWARD
             player.x -= player.angle.sin() * player.speed * game.elapsed;
             player.y -= player.angle.cos() * player.speed * game.elapsed;
@@ -104,7 +137,7 @@ async fn init() {
                 player.y += player.angle.cos() * player.speed * game.elapsed;
             }
         }
-        if game.get_key(Keycode::A).held {
+        if game.get_action("strafe_left").held {
             // MOVE LEFT
             player.x -= player.angle.cos() * player.speed * game.elapsed;
             player.y += player.angle.sin() * player.speed * game.elapsed;
@@ -113,7 +146,7 @@ async fn init() {
                 player.y -= player.angle.sin() * player.speed * game.elapsed;
             }
         }
-        if game.get_key(Keycode::E).held {
+        if game.get_action("strafe_right").held {
             // MOVE RIGHT
             player.x += player.angle.cos() * player.speed * game.elapsed;
             player.y -= player.angle.sin() * player.speed * game.elapsed;
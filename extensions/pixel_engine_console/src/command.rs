@@ -0,0 +1,98 @@
+/// What a command returns: the text to print (nothing if empty), or an error printed in red
+pub type CommandResult = Result<String, String>;
+
+type Handler<T> = Box<dyn FnMut(&mut T, &Args) -> CommandResult>;
+
+/// A command of the [`Console`](crate::Console)
+pub(crate) struct Command<T> {
+    pub(crate) help: String,
+    pub(crate) handler: Handler<T>,
+}
+
+/// A command line split into the name of the command and its arguments
+///
+/// Arguments are separated by whitespace, double quotes group words into a single argument and
+/// `\` escapes the next character
+///
+/// ```
+/// let args = pixel_engine_console::Args::parse(r#"say "hello world" 3"#).unwrap();
+/// assert_eq!(args.name(), "say");
+/// assert_eq!(args.get(0), Some("hello world"));
+/// assert_eq!(args.value::<u32>(1), Ok(3));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    name: String,
+    args: Vec<String>,
+}
+
+impl Args {
+    /// Split a command line, `None` if it is empty
+    #[must_use]
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut words = Vec::new();
+        let mut word: Option<String> = None;
+        let mut quoted = false;
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => word.get_or_insert_with(String::new).extend(chars.next()),
+                '"' => {
+                    quoted = !quoted;
+                    word.get_or_insert_with(String::new);
+                }
+                c if c.is_whitespace() && !quoted => words.extend(word.take()),
+                c => word.get_or_insert_with(String::new).push(c),
+            }
+        }
+        words.extend(word);
+        let mut words = words.into_iter();
+        words.next().map(|name| Self {
+            name,
+            args: words.collect(),
+        })
+    }
+
+    /// The name of the command
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The number of arguments
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    /// Are there no arguments
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    /// Get the argument at `index`
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.args.get(index).map(String::as_str)
+    }
+
+    /// Iterate over the arguments
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.args.iter().map(String::as_str)
+    }
+
+    /// Parse the argument at `index`
+    ///
+    /// # Errors
+    ///
+    /// Returns a message ready to be returned by the command if the argument is missing or
+    /// can't be parsed
+    pub fn value<V: std::str::FromStr>(&self, index: usize) -> Result<V, String> {
+        let arg = self
+            .get(index)
+            .ok_or_else(|| format!("{}: missing argument {}", self.name, index + 1))?;
+        arg.parse()
+            .map_err(|_| format!("{}: invalid argument `{}`", self.name, arg))
+    }
+}
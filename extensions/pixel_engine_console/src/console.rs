@@ -0,0 +1,434 @@
+use crate::command::{Args, Command, CommandResult};
+use crate::console_logger::{LogBuffer, LogSource};
+use pixel_engine::decals::{Decal, DecalText};
+use pixel_engine::inputs::Keycodes;
+use pixel_engine::traits::{DecalDraw, SmartDrawingTrait};
+use pixel_engine::{Color, Engine, Sprite};
+use pixel_engine_textinput::{EngineSource, Event, Motion, Style, TextInput, TextInputSource};
+use std::collections::{BTreeMap, VecDeque};
+
+const PROMPT: &str = "> ";
+const ECHO: Color = Color::new(160, 160, 160);
+
+fn level_color(level: log::Level) -> Color {
+    match level {
+        log::Level::Error => Color::RED,
+        log::Level::Warn => Color::YELLOW,
+        log::Level::Info => Color::WHITE,
+        log::Level::Debug => Color::CYAN,
+        log::Level::Trace => Color::GREY,
+    }
+}
+
+/// An overlay console showing the `log` records and running commands
+///
+/// Commands get a `&mut T`, the state of the game they act on, given to [`Console::update`].
+/// `help` and `clear` are always available.
+///
+/// ```no_run
+/// use pixel_engine_console::{Console, ConsoleLogger};
+///
+/// struct World {
+///     gravity: f32,
+/// }
+///
+/// pixel_engine::launch(async move {
+///     let game = pixel_engine::EngineWrapper::new("Console".to_owned(), (320, 180, 3)).await;
+///     let logs = ConsoleLogger::<256>::new(log::LevelFilter::Info).init().unwrap();
+///     let mut console = Console::new().logger(logs);
+///     console.register("gravity", "gravity <value>: set the gravity", |world: &mut World, args| {
+///         world.gravity = args.value(0)?;
+///         Ok(format!("gravity is now {}", world.gravity))
+///     });
+///     let mut world = World { gravity: 9.81 };
+///     game.run(move |engine| {
+///         if !console.update(engine, &mut world) {
+///             // Only read the game's inputs while the console is closed
+///         }
+///         // Draw the game, then the console on top of it
+///         console.draw(engine);
+///         Ok(true)
+///     });
+/// });
+/// ```
+pub struct Console<T = ()> {
+    open: bool,
+    toggle_key: Keycodes,
+    input: TextInput,
+    source: EngineSource,
+    lines: VecDeque<(Color, String)>,
+    max_lines: usize,
+    scroll: usize,
+    height: f32,
+    background_color: Color,
+    background: Option<Decal>,
+    history: Vec<String>,
+    history_index: Option<usize>,
+    draft: String,
+    commands: BTreeMap<String, Command<T>>,
+    logs: Option<Box<dyn LogSource>>,
+}
+
+impl<T> std::fmt::Debug for Console<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Console")
+            .field("open", &self.open)
+            .field("toggle_key", &self.toggle_key)
+            .field("input", &self.input)
+            .field("lines", &self.lines)
+            .field("history", &self.history)
+            .field("commands", &self.commands.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Default for Console<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Console<T> {
+    /// Create a closed console, opened with the key under `Escape` (`` ` `` on QWERTY),
+    /// covering half of the screen and keeping the last 500 lines
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            toggle_key: Keycodes::Grave,
+            input: TextInput::new(),
+            source: EngineSource::new(),
+            lines: VecDeque::new(),
+            max_lines: 500,
+            scroll: 0,
+            height: 0.5,
+            background_color: Color::new_with_alpha(0, 0, 0, 200),
+            background: None,
+            history: Vec::new(),
+            history_index: None,
+            draft: String::new(),
+            commands: BTreeMap::new(),
+            logs: None,
+        }
+    }
+
+    /// Set the key opening and closing the console
+    #[must_use]
+    pub fn toggle_key(mut self, key: Keycodes) -> Self {
+        self.toggle_key = key;
+        self
+    }
+
+    /// Show the records of a [`ConsoleLogger`](crate::ConsoleLogger)
+    #[must_use]
+    pub fn logger<const BUFFER_SIZE: usize>(mut self, logs: LogBuffer<BUFFER_SIZE>) -> Self {
+        self.logs = Some(Box::new(logs));
+        self
+    }
+
+    /// Set the number of lines kept, older lines are dropped
+    #[must_use]
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines.max(1);
+        self
+    }
+
+    /// Set the part of the screen (between 0 and 1) covered by the console
+    #[must_use]
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the color (and transparency) behind the text, the game shows through it according to
+    /// its alpha, in a window as well as headless
+    #[must_use]
+    pub fn background(mut self, color: Color) -> Self {
+        self.background_color = color;
+        self
+    }
+
+    /// Add a command, replacing any command with the same name.
+    /// `help` is shown by the `help` command, starting it with the usage is a good idea
+    pub fn register<F>(&mut self, name: &str, help: &str, handler: F)
+    where
+        F: FnMut(&mut T, &Args) -> CommandResult + 'static,
+    {
+        self.commands.insert(
+            name.to_owned(),
+            Command {
+                help: help.to_owned(),
+                handler: Box::new(handler),
+            },
+        );
+    }
+
+    /// Is the console shown
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Show or hide the console
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    /// The lines shown by the console, oldest first
+    pub fn lines(&self) -> impl Iterator<Item = (Color, &str)> {
+        self.lines
+            .iter()
+            .map(|(color, text)| (*color, text.as_str()))
+    }
+
+    /// The commands that were run, oldest first
+    #[must_use]
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Add a white line to the console
+    pub fn print(&mut self, text: &str) {
+        self.print_colored(text, Color::WHITE);
+    }
+
+    /// Add a line to the console, `\n` starts new lines
+    pub fn print_colored(&mut self, text: &str, color: Color) {
+        for line in text.lines() {
+            if self.lines.len() == self.max_lines {
+                self.lines.pop_front();
+            }
+            self.lines.push_back((color, line.to_owned()));
+        }
+    }
+
+    /// Remove all the lines
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.scroll = 0;
+    }
+
+    /// Run a command line as if it was typed in the console
+    pub fn execute(&mut self, ctx: &mut T, line: &str) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        if self.history.last().map(String::as_str) != Some(line) {
+            self.history.push(line.to_owned());
+        }
+        self.print_colored(&format!("{PROMPT}{line}"), ECHO);
+        self.scroll = 0;
+        let Some(args) = Args::parse(line) else {
+            return;
+        };
+        let result = match args.name() {
+            "help" => self.help(args.get(0)),
+            "clear" => {
+                self.clear();
+                Ok(String::new())
+            }
+            name => match self.commands.get_mut(name) {
+                Some(command) => (command.handler)(ctx, &args),
+                None => Err(format!("unknown command `{name}`, try `help`")),
+            },
+        };
+        match result {
+            Ok(text) => self.print(&text),
+            Err(text) => self.print_colored(&text, Color::RED),
+        }
+    }
+
+    fn help(&self, command: Option<&str>) -> CommandResult {
+        const BUILTINS: [(&str, &str); 2] = [
+            ("clear", "clear: remove all the lines"),
+            (
+                "help",
+                "help [command]: list the commands or show the help of one",
+            ),
+        ];
+        let mut all: BTreeMap<&str, &str> = BUILTINS.into_iter().collect();
+        all.extend(
+            self.commands
+                .iter()
+                .map(|(name, command)| (name.as_str(), command.help.as_str())),
+        );
+        match command {
+            Some(name) => all
+                .get(name)
+                .map(|help| (*help).to_owned())
+                .ok_or_else(|| format!("unknown command `{name}`")),
+            None => Ok(all
+                .into_iter()
+                .map(|(name, help)| format!("{name:<10} {help}"))
+                .collect::<Vec<_>>()
+                .join("\n")),
+        }
+    }
+
+    /// Complete the command name being typed, listing the candidates if there are several
+    fn complete(&mut self) {
+        let typed = self.input.text().to_owned();
+        if typed.contains(char::is_whitespace) {
+            return;
+        }
+        let candidates: Vec<String> = ["clear", "help"]
+            .into_iter()
+            .chain(self.commands.keys().map(String::as_str))
+            .filter(|name| name.starts_with(&typed))
+            .map(str::to_owned)
+            .collect();
+        match candidates.as_slice() {
+            [] => {}
+            [name] => self.input.set_text(&format!("{name} ")),
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.chars().count(), |len, name| {
+                    first
+                        .chars()
+                        .zip(name.chars())
+                        .take(len)
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                });
+                let prefix: String = first.chars().take(common).collect();
+                self.input.set_text(&prefix);
+                self.print_colored(&candidates.join("  "), ECHO);
+            }
+        }
+    }
+
+    fn browse_history(&mut self, older: bool) {
+        let index = match (self.history_index, older) {
+            (_, true) if self.history.is_empty() => return,
+            (None, true) => {
+                self.draft = self.input.text().to_owned();
+                self.history.len() - 1
+            }
+            (Some(index), true) => index.saturating_sub(1),
+            (None, false) => return,
+            (Some(index), false) if index + 1 < self.history.len() => index + 1,
+            (Some(_), false) => {
+                self.history_index = None;
+                let draft = std::mem::take(&mut self.draft);
+                self.input.set_text(&draft);
+                return;
+            }
+        };
+        self.history_index = Some(index);
+        self.input.set_text(&self.history[index]);
+    }
+
+    /// Read the logs and, if the console is open, the inputs of the last frame.
+    /// Returns true if the console is open, the game should then ignore the keyboard
+    pub fn update(&mut self, engine: &Engine, ctx: &mut T) -> bool {
+        if let Some(logs) = &self.logs {
+            for line in logs.drain() {
+                self.print_colored(&line.text, level_color(line.level));
+            }
+        }
+        if engine.get_key(self.toggle_key).pressed {
+            self.open = !self.open;
+            // Drop the character typed by the toggle key
+            self.source.update(engine);
+            while self.source.next_event().is_some() {}
+            return self.open;
+        }
+        if !self.open {
+            return false;
+        }
+        self.source.update(engine);
+        while let Some(event) = self.source.next_event() {
+            match event {
+                Event::Move {
+                    motion: Motion::Up,
+                    select: false,
+                } => self.browse_history(true),
+                Event::Move {
+                    motion: Motion::Down,
+                    select: false,
+                } => self.browse_history(false),
+                Event::Newline => {
+                    let line = self.input.text().to_owned();
+                    self.input.clear();
+                    self.history_index = None;
+                    self.execute(ctx, &line);
+                }
+                event => {
+                    self.input.handle_event(event);
+                }
+            }
+        }
+        if engine.get_key(Keycodes::Tab).pressed {
+            self.complete();
+        }
+        if engine.get_key(Keycodes::PageUp).pressed {
+            self.scroll = (self.scroll + 4).min(self.lines.len().saturating_sub(1));
+        }
+        if engine.get_key(Keycodes::PageDown).pressed {
+            self.scroll = self.scroll.saturating_sub(4);
+        }
+        true
+    }
+
+    /// Draw the console with decals, on top of everything drawn before, if it is open.
+    /// Call it at the end of the frame
+    pub fn draw(&mut self, engine: &mut Engine) {
+        #![allow(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss
+        )]
+        if !self.open {
+            return;
+        }
+        let size = engine.get_size();
+        let height = (size.y as f32 * self.height).floor();
+        let background = self.background.get_or_insert_with(|| {
+            engine.create_decal(&Sprite::new_with_color(1, 1, Color::WHITE))
+        });
+        engine.draw_decal_scaled_tinted(
+            (0.0, 0.0),
+            background,
+            (size.x as f32, height),
+            self.background_color,
+        );
+
+        let columns = (size.x / 8).max(1) as usize;
+        let rows = (height / 8.0) as usize;
+        if rows == 0 {
+            return;
+        }
+        // The input line is at the bottom, the (wrapped) lines above it, newest at the bottom
+        let input_y = (rows - 1) as f32 * 8.0;
+        engine.draw_text_decal((0.0, input_y), PROMPT, (1.0, 1.0), ECHO);
+        self.input.draw_decal(
+            engine,
+            (PROMPT.len() as f32 * 8.0, input_y),
+            (1.0, 1.0),
+            background,
+            &Style::default(),
+        );
+        let wrapped = self.lines.iter().rev().flat_map(|(color, text)| {
+            let chars: Vec<char> = text.chars().collect();
+            let mut parts: Vec<(Color, String)> = chars
+                .chunks(columns)
+                .map(|chunk| (*color, chunk.iter().collect()))
+                .collect();
+            if parts.is_empty() {
+                parts.push((*color, String::new()));
+            }
+            parts.into_iter().rev()
+        });
+        for (row, (color, text)) in wrapped.skip(self.scroll).take(rows - 1).enumerate() {
+            let y = input_y - (row + 1) as f32 * 8.0;
+            engine.draw_text_decal((0.0, y), text, (1.0, 1.0), color);
+        }
+    }
+
+    /// Destroy the decal used by the console, call it when the game stops
+    pub fn destroy(&mut self, engine: &mut Engine) {
+        if let Some(background) = self.background.take() {
+            engine.destroy_decal(&background);
+        }
+    }
+}
@@ -1,9 +1,143 @@
+use heapless::Deque;
+use std::sync::{Arc, RwLock};
+
+/// A log record kept by a [`ConsoleLogger`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    /// The level of the record
+    pub level: log::Level,
+    /// The formatted message
+    pub text: String,
+}
+
+type Buffer<const BUFFER_SIZE: usize> = Arc<RwLock<Deque<LogLine, BUFFER_SIZE>>>;
+
+/// A [`log::Log`] keeping the last `BUFFER_SIZE` records for a [`Console`](crate::Console) and
+/// forwarding every record to `PASSTHROUGH` (`env_logger`, a file logger...)
 pub struct ConsoleLogger<const BUFFER_SIZE: usize, PASSTHROUGH: log::Log = SinkLogger> {
     passthrough: Box<PASSTHROUGH>,
     max_level: log::LevelFilter,
-    inner_buffer: std::sync::Arc<std::sync::RwLock<heapless::Deque<String, BUFFER_SIZE>>>,
+    inner_buffer: Buffer<BUFFER_SIZE>,
+}
+
+impl<const BUFFER_SIZE: usize, PASSTHROUGH: log::Log> std::fmt::Debug
+    for ConsoleLogger<BUFFER_SIZE, PASSTHROUGH>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConsoleLogger")
+            .field("max_level", &self.max_level)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const BUFFER_SIZE: usize> ConsoleLogger<BUFFER_SIZE> {
+    /// Keep the records up to `max_level`, without passing them to another logger
+    #[must_use]
+    pub fn new(max_level: log::LevelFilter) -> Self {
+        Self::with_passthrough(SinkLogger, max_level)
+    }
+}
+
+impl<const BUFFER_SIZE: usize, PASSTHROUGH: log::Log> ConsoleLogger<BUFFER_SIZE, PASSTHROUGH> {
+    /// Keep the records up to `max_level` and pass all the records to `passthrough`, which
+    /// filters them itself
+    pub fn with_passthrough(passthrough: PASSTHROUGH, max_level: log::LevelFilter) -> Self {
+        Self {
+            passthrough: Box::new(passthrough),
+            max_level,
+            inner_buffer: Arc::new(RwLock::new(Deque::new())),
+        }
+    }
+
+    /// Get a handle on the kept records, to give to [`Console::logger`](crate::Console::logger)
+    #[must_use]
+    pub fn buffer(&self) -> LogBuffer<BUFFER_SIZE> {
+        LogBuffer(Arc::clone(&self.inner_buffer))
+    }
+
+    /// Install the logger as the global logger of the `log` crate
+    ///
+    /// Without a passthrough, the global maximum level is `max_level`. With one, every record is
+    /// let through, since the passthrough filters them itself
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a global logger was already set
+    pub fn init(self) -> Result<LogBuffer<BUFFER_SIZE>, log::SetLoggerError>
+    where
+        PASSTHROUGH: 'static,
+    {
+        let buffer = self.buffer();
+        let max_level =
+            if std::any::TypeId::of::<PASSTHROUGH>() == std::any::TypeId::of::<SinkLogger>() {
+                self.max_level
+            } else {
+                log::LevelFilter::Trace
+            };
+        log::set_logger(Box::leak(Box::new(self)))?;
+        log::set_max_level(max_level);
+        Ok(buffer)
+    }
+}
+
+impl<const BUFFER_SIZE: usize, PASSTHROUGH: log::Log> log::Log
+    for ConsoleLogger<BUFFER_SIZE, PASSTHROUGH>
+{
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.max_level || self.passthrough.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.level() <= self.max_level {
+            if let Ok(mut buffer) = self.inner_buffer.write() {
+                if buffer.is_full() {
+                    buffer.pop_front();
+                }
+                let _ = buffer.push_back(LogLine {
+                    level: record.level(),
+                    text: record.args().to_string(),
+                });
+            }
+        }
+        if self.passthrough.enabled(record.metadata()) {
+            self.passthrough.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.passthrough.flush();
+    }
+}
+
+/// A shared handle on the records kept by a [`ConsoleLogger`]
+#[derive(Debug, Clone)]
+pub struct LogBuffer<const BUFFER_SIZE: usize>(Buffer<BUFFER_SIZE>);
+
+impl<const BUFFER_SIZE: usize> LogBuffer<BUFFER_SIZE> {
+    /// Remove and return the kept records, oldest first
+    #[must_use]
+    pub fn drain(&self) -> Vec<LogLine> {
+        self.0.write().map_or_else(
+            |_| Vec::new(),
+            |mut buffer| std::iter::from_fn(|| buffer.pop_front()).collect(),
+        )
+    }
+}
+
+/// Something the [`Console`](crate::Console) can read log records from, whatever the size of
+/// the buffer
+pub(crate) trait LogSource {
+    fn drain(&self) -> Vec<LogLine>;
+}
+
+impl<const BUFFER_SIZE: usize> LogSource for LogBuffer<BUFFER_SIZE> {
+    fn drain(&self) -> Vec<LogLine> {
+        LogBuffer::drain(self)
+    }
 }
 
+/// A [`log::Log`] discarding everything
+#[derive(Debug, Clone, Copy)]
 pub struct SinkLogger;
 
 impl log::Log for SinkLogger {
@@ -1,3 +1,18 @@
+//! An in-game developer console for [`pixel_engine`].
+//!
+//! [`ConsoleLogger`] keeps the last records of the `log` crate, [`Console`] shows them over the
+//! game, colored by level, and runs the commands typed in it, with history (up/down) and
+//! completion of command names (tab). See [`Console`] for an example.
+
+#![warn(clippy::pedantic)]
+#![deny(missing_docs, missing_debug_implementations)]
+
 extern crate log;
 
+mod command;
+mod console;
 mod console_logger;
+
+pub use command::{Args, CommandResult};
+pub use console::Console;
+pub use console_logger::{ConsoleLogger, LogBuffer, LogLine, SinkLogger};
@@ -0,0 +1,210 @@
+use log::Log;
+use pixel_engine::inputs::{InputEvent, Keycodes};
+use pixel_engine::testing::{run_frames, InputScript};
+use pixel_engine::traits::*;
+use pixel_engine::{Color, Engine, EngineWrapper, Game};
+use pixel_engine_console::{Args, Console, ConsoleLogger};
+
+fn type_line(script: InputScript, frame: usize, text: &str) -> InputScript {
+    text.chars().fold(script, |script, c| {
+        script.at(frame, InputEvent::Character(c))
+    })
+}
+
+fn counter_console() -> Console<i32> {
+    let mut console = Console::new();
+    console.register("add", "add <n>: add n to the counter", |counter, args| {
+        *counter += args.value::<i32>(0)?;
+        Ok(format!("counter = {counter}"))
+    });
+    console.register("address", "address: where is the counter", |_, _| {
+        Ok("here".to_owned())
+    });
+    console
+}
+
+#[test]
+fn parse_args() {
+    let args = Args::parse(r#"  spawn "big \"bad\" wolf"   2 x\ y "" "#).unwrap();
+    assert_eq!(args.name(), "spawn");
+    assert_eq!(
+        args.iter().collect::<Vec<_>>(),
+        vec![r#"big "bad" wolf"#, "2", "x y", ""]
+    );
+    assert_eq!(args.value::<u8>(1), Ok(2));
+    assert!(args.value::<u8>(2).is_err());
+    assert!(args.value::<u8>(4).is_err());
+    assert_eq!(Args::parse("   "), None);
+}
+
+#[test]
+fn commands() {
+    let mut console = counter_console();
+    let mut counter = 0;
+    console.execute(&mut counter, "add 5");
+    console.execute(&mut counter, "add x");
+    console.execute(&mut counter, "nope");
+    console.execute(&mut counter, "help add");
+    assert_eq!(counter, 5);
+    assert_eq!(
+        console.lines().collect::<Vec<_>>(),
+        vec![
+            (Color::new(160, 160, 160), "> add 5"),
+            (Color::WHITE, "counter = 5"),
+            (Color::new(160, 160, 160), "> add x"),
+            (Color::RED, "add: invalid argument `x`"),
+            (Color::new(160, 160, 160), "> nope"),
+            (Color::RED, "unknown command `nope`, try `help`"),
+            (Color::new(160, 160, 160), "> help add"),
+            (Color::WHITE, "add <n>: add n to the counter"),
+        ]
+    );
+    console.execute(&mut counter, "help");
+    let help: Vec<&str> = console.lines().map(|(_, l)| l).skip(9).collect();
+    assert_eq!(help.len(), 4);
+    assert!(help[0].starts_with("add ") && help[0].ends_with("add <n>: add n to the counter"));
+    assert!(help[1].starts_with("address "));
+    assert!(help[2].starts_with("clear "));
+    assert!(help[3].starts_with("help "));
+    console.execute(&mut counter, "clear");
+    assert_eq!(console.lines().count(), 0);
+}
+
+#[test]
+fn logs() {
+    let logger = ConsoleLogger::<4>::new(log::LevelFilter::Debug);
+    let mut console = Console::new().logger(logger.buffer());
+    for (level, text) in [
+        (log::Level::Info, "loading"),
+        (log::Level::Trace, "hidden"),
+        (log::Level::Error, "failed"),
+        (log::Level::Warn, "slow"),
+        (log::Level::Debug, "a"),
+        (log::Level::Debug, "b"),
+    ] {
+        logger.log(
+            &log::Record::builder()
+                .level(level)
+                .args(format_args!("{}", text))
+                .build(),
+        );
+    }
+    run_frames((8, 8), 1, &InputScript::new(), |engine| {
+        console.update(engine, &mut ());
+        Ok(true)
+    })
+    .unwrap();
+    // Only the last 4 records are kept
+    assert_eq!(
+        console.lines().collect::<Vec<_>>(),
+        vec![
+            (Color::RED, "failed"),
+            (Color::YELLOW, "slow"),
+            (Color::CYAN, "a"),
+            (Color::CYAN, "b"),
+        ]
+    );
+}
+
+#[test]
+fn typing_history_and_completion() {
+    let script = InputScript::new()
+        .key_tap(1, 1, Keycodes::Grave)
+        .at(1, InputEvent::Character('`'));
+    let script = type_line(script, 2, "ad");
+    let script = script.key_tap(3, 1, Keycodes::Tab);
+    let script = type_line(script, 4, " 2");
+    let script = script
+        .key_tap(7, 1, Keycodes::Return)
+        .key_tap(8, 1, Keycodes::Up)
+        .key_tap(10, 1, Keycodes::Return)
+        .key_tap(11, 1, Keycodes::Grave)
+        .at(11, InputEvent::Character('`'));
+    let script = type_line(script, 12, "add 100");
+    let script = script.key_tap(13, 1, Keycodes::Return);
+
+    let mut console = counter_console();
+    let mut counter = 0;
+    let mut open = Vec::new();
+    run_frames((64, 64), 15, &script, |engine| {
+        open.push(console.update(engine, &mut counter));
+        Ok(true)
+    })
+    .unwrap();
+    assert_eq!(counter, 4);
+    assert_eq!(console.history(), ["add 2"]);
+    assert_eq!(&open[..3], [false, true, true]);
+    assert!(!open[11] && !open[14]);
+    assert_eq!(
+        console.lines().map(|(_, l)| l).collect::<Vec<_>>(),
+        vec![
+            "add  address",
+            "> add 2",
+            "counter = 2",
+            "> add 2",
+            "counter = 4"
+        ]
+    );
+}
+
+struct Overlay(Console);
+
+impl Game for Overlay {
+    fn on_user_update(
+        &mut self,
+        engine: &mut Engine,
+        _elapsed: f64,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        engine.clear(Color::BLUE);
+        self.0.update(engine, &mut ());
+        self.0.draw(engine);
+        Ok(true)
+    }
+
+    fn on_user_destroy(&mut self, engine: &mut Engine) {
+        self.0.destroy(engine);
+    }
+}
+
+#[test]
+fn draw_over_the_game() {
+    let mut console = Console::new().background(Color::BLACK);
+    console.print_colored("hello", Color::RED);
+    console.set_open(true);
+    let frame = EngineWrapper::new_headless((32, 32))
+        .run_app_headless(1, Overlay(console))
+        .unwrap();
+    let row_colors = |y0: u32, y1: u32| {
+        let mut colors = Vec::new();
+        for y in y0..y1 {
+            for x in 0..32 {
+                let c = frame.get_pixel(x, y);
+                if !colors.contains(&c) {
+                    colors.push(c);
+                }
+            }
+        }
+        colors
+    };
+    // "hello" on the first row, the prompt on the second, the game below
+    let first = row_colors(0, 8);
+    assert!(first.contains(&Color::RED) && first.contains(&Color::BLACK));
+    assert!(!first.contains(&Color::BLUE));
+    assert!(!row_colors(8, 16).contains(&Color::BLUE));
+    assert_eq!(row_colors(16, 32), vec![Color::BLUE]);
+}
+
+#[test]
+fn translucent_background() {
+    let mut console = Console::new().background(Color::new_with_alpha(0, 0, 0, 128));
+    console.set_open(true);
+    let frame = EngineWrapper::new_headless((32, 32))
+        .run_app_headless(1, Overlay(console))
+        .unwrap();
+    // The blue of the game is darkened under the console, not hidden. The decals are blended in
+    // linear space, so half of the blue is lighter than half of its byte
+    let under = frame.get_pixel(31, 0);
+    assert_eq!((under.r, under.g), (0, 0));
+    assert!(under.b > 170 && under.b < 200, "{under:?}");
+    assert_eq!(frame.get_pixel(31, 31), Color::BLUE);
+}
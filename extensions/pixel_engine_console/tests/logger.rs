@@ -0,0 +1,34 @@
+use pixel_engine_console::ConsoleLogger;
+use std::sync::{Arc, Mutex};
+
+/// A passthrough keeping every record it is given
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl log::Log for Recorder {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.0.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+// Installing the global logger works once per process, so this test has its own binary
+#[test]
+fn passthrough_gets_the_levels_the_console_drops() {
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let buffer = ConsoleLogger::<4, _>::with_passthrough(
+        Recorder(Arc::clone(&records)),
+        log::LevelFilter::Warn,
+    )
+    .init()
+    .unwrap();
+    log::warn!("slow");
+    log::trace!("details");
+    assert_eq!(*records.lock().unwrap(), ["slow", "details"]);
+    let kept: Vec<_> = buffer.drain().into_iter().map(|line| line.text).collect();
+    assert_eq!(kept, ["slow"]);
+}
@@ -0,0 +1,146 @@
+use crate::{Event, Motion, TextInputSource};
+use pixel_engine::events::Event as EngineEvent;
+use pixel_engine::inputs::{InputEvent, Keycodes, Modifiers};
+use pixel_engine::Engine;
+use std::collections::{HashSet, VecDeque};
+
+/// The keys that repeat while held, when the operating system repeats them
+const REPEATED_KEYS: [Keycodes; 10] = [
+    Keycodes::Left,
+    Keycodes::Right,
+    Keycodes::Up,
+    Keycodes::Down,
+    Keycodes::Home,
+    Keycodes::End,
+    Keycodes::Back,
+    Keycodes::Delete,
+    Keycodes::Return,
+    Keycodes::NumpadEnter,
+];
+
+/// A [`TextInputSource`] reading the inputs of an [`Engine`]
+///
+/// Call [`EngineSource::update`] once per frame, before updating the [`TextInput`](crate::TextInput).
+/// It walks [`Engine::events`], so the keys and the characters typed in a frame are applied in
+/// the order they arrived. Typed text comes from [`InputEvent::Character`], so it follows the
+/// keyboard layout.
+/// Arrows, home/end, backspace/delete and enter repeat while held, when the operating system
+/// repeats them (see [`Input::repeated`](pixel_engine::inputs::Input::repeated)). With `Ctrl`
+/// they work on whole words (or the whole text for home/end) and with `Shift` they extend the
+/// selection.
+/// `Ctrl+A` selects everything
+#[derive(Debug, Clone)]
+pub struct EngineSource {
+    events: VecDeque<Event>,
+    /// The modifiers last reported by the system
+    modifiers: Modifiers,
+    /// The modifier keys that are down
+    modifier_keys: HashSet<Keycodes>,
+}
+
+impl Default for EngineSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EngineSource {
+    /// Create a source with no pending event
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            events: VecDeque::new(),
+            modifiers: Modifiers::empty(),
+            modifier_keys: HashSet::new(),
+        }
+    }
+
+    /// Read the inputs of the last frame
+    pub fn update(&mut self, engine: &Engine) {
+        for event in engine.events() {
+            let input = match event {
+                EngineEvent::Input(input) => *input,
+                _ => continue,
+            };
+            match input {
+                InputEvent::Modifiers(modifiers) => self.modifiers = modifiers,
+                InputEvent::KeyPress(key) | InputEvent::KeyRepeat(key) => {
+                    if modifier_of(key).is_some() {
+                        self.modifier_keys.insert(key);
+                    }
+                    let modifiers = self.modifiers();
+                    let (shift, ctrl) = (modifiers.shift(), modifiers.ctrl());
+                    if REPEATED_KEYS.contains(&key) {
+                        self.push_key(key, ctrl, shift);
+                    } else if ctrl && input == InputEvent::KeyPress(Keycodes::A) {
+                        self.events.push_back(Event::SelectAll);
+                    }
+                }
+                InputEvent::KeyRelease(key) => {
+                    self.modifier_keys.remove(&key);
+                }
+                InputEvent::Character(c) => self.events.push_back(Event::Char(c)),
+                _ => {}
+            }
+        }
+    }
+
+    /// The modifiers reported by the system or from the modifier keys that are down, like
+    /// [`Engine::get_modifiers`] at this point of the frame
+    fn modifiers(&self) -> Modifiers {
+        self.modifier_keys
+            .iter()
+            .filter_map(|key| modifier_of(*key))
+            .fold(self.modifiers, |modifiers, modifier| modifiers | modifier)
+    }
+
+    fn push_key(&mut self, key: Keycodes, ctrl: bool, shift: bool) {
+        let motion = |normal, with_ctrl| if ctrl { with_ctrl } else { normal };
+        let event = match key {
+            Keycodes::Left => Event::Move {
+                motion: motion(Motion::Left, Motion::WordLeft),
+                select: shift,
+            },
+            Keycodes::Right => Event::Move {
+                motion: motion(Motion::Right, Motion::WordRight),
+                select: shift,
+            },
+            Keycodes::Up => Event::Move {
+                motion: Motion::Up,
+                select: shift,
+            },
+            Keycodes::Down => Event::Move {
+                motion: Motion::Down,
+                select: shift,
+            },
+            Keycodes::Home => Event::Move {
+                motion: motion(Motion::LineStart, Motion::TextStart),
+                select: shift,
+            },
+            Keycodes::End => Event::Move {
+                motion: motion(Motion::LineEnd, Motion::TextEnd),
+                select: shift,
+            },
+            Keycodes::Back => Event::Delete(motion(Motion::Left, Motion::WordLeft)),
+            Keycodes::Delete => Event::Delete(motion(Motion::Right, Motion::WordRight)),
+            _ => Event::Newline,
+        };
+        self.events.push_back(event);
+    }
+}
+
+fn modifier_of(key: Keycodes) -> Option<Modifiers> {
+    match key {
+        Keycodes::LShift | Keycodes::RShift => Some(Modifiers::SHIFT),
+        Keycodes::LControl | Keycodes::RControl => Some(Modifiers::CTRL),
+        Keycodes::LAlt | Keycodes::RAlt => Some(Modifiers::ALT),
+        Keycodes::LWin | Keycodes::RWin => Some(Modifiers::LOGO),
+        _ => None,
+    }
+}
+
+impl TextInputSource for EngineSource {
+    fn next_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+}
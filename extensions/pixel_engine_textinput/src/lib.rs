@@ -1,13 +1,86 @@
+//! A single or multi line text field for [`pixel_engine`].
+//!
+//! A [`TextInput`] holds the text, the cursor and the selection. It is edited with [`Event`]s,
+//! coming from a [`TextInputSource`] such as [`EngineSource`], which turns the inputs of an
+//! [`Engine`](pixel_engine::Engine) into events, with key repeat.
+//!
+//! ```no_run
+//! use pixel_engine::traits::*;
+//! use pixel_engine_textinput::{EngineSource, Style, TextInput};
+//!
+//! pixel_engine::launch(async move {
+//!     let game = pixel_engine::EngineWrapper::new("Text".to_owned(), (160, 90, 4)).await;
+//!     let mut field = TextInput::new().max_length(Some(16));
+//!     let mut source = EngineSource::new();
+//!     game.run(move |engine| {
+//!         source.update(engine);
+//!         field.update(&mut source);
+//!         engine.clear(pixel_engine::Color::BLACK);
+//!         field.draw(engine, (4, 4), 1, &Style::default());
+//!         Ok(true)
+//!     });
+//! });
+//! ```
+
+#![warn(clippy::pedantic)]
+#![deny(missing_docs, missing_debug_implementations)]
+
+mod engine_source;
+mod text_input;
+
+pub use engine_source::EngineSource;
+pub use text_input::{Style, TextInput};
+
+/// Something producing the editing events of a [`TextInput`]
 pub trait TextInputSource {
-    fn get_events(&self) -> Option<Event>;
+    /// Get the next pending event, `None` once they were all consumed
+    fn next_event(&mut self) -> Option<Event>;
 }
 
-#[derive(Clone, Copy, Debug)]
+/// A cursor movement
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Motion {
+    /// One character to the left
+    Left,
+    /// One character to the right
+    Right,
+    /// Same column on the previous line, or the start of the text on the first line
+    Up,
+    /// Same column on the next line, or the end of the text on the last line
+    Down,
+    /// To the start of the current or previous word
+    WordLeft,
+    /// To the end of the current or next word
+    WordRight,
+    /// To the start of the current line
+    LineStart,
+    /// To the end of the current line
+    LineEnd,
+    /// To the start of the text
+    TextStart,
+    /// To the end of the text
+    TextEnd,
+    /// To the given character index, clamped to the length of the text
+    Index(usize),
+}
+
+/// An editing action on a [`TextInput`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Event {
-    CursorLeft,
-    CursorRight,
-    CursorMove { index: usize },
+    /// Move the cursor, extending the selection if `select` is true or dropping it otherwise
+    Move {
+        /// Where to move the cursor
+        motion: Motion,
+        /// Extend the selection instead of dropping it
+        select: bool,
+    },
+    /// Replace the selection with a character
     Char(char),
+    /// Replace the selection with a new line, ignored by single line fields
+    Newline,
+    /// Delete the selection, or from the cursor up to where the motion would move it (for
+    /// example [`Motion::Left`] for backspace and [`Motion::Right`] for delete)
+    Delete(Motion),
+    /// Select the whole text
+    SelectAll,
 }
-
-struct TextInput;
@@ -0,0 +1,490 @@
+use crate::{Event, Motion, TextInputSource};
+use pixel_engine::decals::{Decal, DecalText};
+use pixel_engine::traits::ShapesTrait;
+use pixel_engine::vector2::{Vf2d, Vi2d};
+use pixel_engine::Color;
+
+/// The colors of a [`TextInput`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Style {
+    /// Color of the text
+    pub text: Color,
+    /// Color of the selected text
+    pub selected_text: Color,
+    /// Background of the selected text
+    pub selection: Color,
+    /// Color of the cursor, `None` to hide it (when the field isn't focused or to make it blink)
+    pub cursor: Option<Color>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            text: Color::WHITE,
+            selected_text: Color::BLACK,
+            selection: Color::WHITE,
+            cursor: Some(Color::WHITE),
+        }
+    }
+}
+
+type Validator = Box<dyn Fn(&str) -> bool>;
+
+/// An editable text with a cursor and a selection
+///
+/// Positions are counted in characters, not bytes. The text is drawn with the engine's 8x8 font,
+/// so characters it doesn't have are drawn as `?`
+pub struct TextInput {
+    text: String,
+    cursor: usize,
+    anchor: Option<usize>,
+    multiline: bool,
+    max_length: Option<usize>,
+    validator: Option<Validator>,
+}
+
+impl std::fmt::Debug for TextInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextInput")
+            .field("text", &self.text)
+            .field("cursor", &self.cursor)
+            .field("anchor", &self.anchor)
+            .field("multiline", &self.multiline)
+            .field("max_length", &self.max_length)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for TextInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl TextInput {
+    /// Create an empty single line field, without length limit
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            cursor: 0,
+            anchor: None,
+            multiline: false,
+            max_length: None,
+            validator: None,
+        }
+    }
+
+    /// Allow new lines in the field
+    #[must_use]
+    pub fn multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    /// Limit the number of characters of the text, edits making it longer are refused
+    #[must_use]
+    pub fn max_length(mut self, max_length: Option<usize>) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Only accept edits for which the resulting text passes `validator`
+    ///
+    /// ```
+    /// let field = pixel_engine_textinput::TextInput::new()
+    ///     .validator(|text| text.chars().all(|c| c.is_ascii_digit()));
+    /// ```
+    #[must_use]
+    pub fn validator<F: Fn(&str) -> bool + 'static>(mut self, validator: F) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Start with the given text, the cursor is put at the end
+    #[must_use]
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    /// The current text
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the text, bypassing the validator and the length limit. The cursor is put at the
+    /// end and the selection is dropped
+    pub fn set_text(&mut self, text: &str) {
+        self.text = if self.multiline {
+            text.to_owned()
+        } else {
+            text.replace('\n', " ")
+        };
+        self.cursor = self.len();
+        self.anchor = None;
+    }
+
+    /// Empty the field
+    pub fn clear(&mut self) {
+        self.set_text("");
+    }
+
+    /// Is the field a multi line one
+    #[must_use]
+    pub fn is_multiline(&self) -> bool {
+        self.multiline
+    }
+
+    /// The position of the cursor, in characters
+    #[must_use]
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// The selected range, in characters (start inclusive, end exclusive), if something is
+    /// selected
+    #[must_use]
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        self.anchor
+            .filter(|anchor| *anchor != self.cursor)
+            .map(|anchor| (anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    /// The selected text, empty if nothing is selected
+    #[must_use]
+    pub fn selected_text(&self) -> &str {
+        self.selection().map_or("", |(start, end)| {
+            &self.text[self.byte(start)..self.byte(end)]
+        })
+    }
+
+    /// Apply all the events of the source, returns true if the text changed
+    pub fn update<S: TextInputSource>(&mut self, source: &mut S) -> bool {
+        let mut changed = false;
+        while let Some(event) = source.next_event() {
+            changed |= self.handle_event(event);
+        }
+        changed
+    }
+
+    /// Apply an event, returns true if the text changed
+    pub fn handle_event(&mut self, event: Event) -> bool {
+        match event {
+            Event::Move { motion, select } => {
+                self.move_cursor(motion, select);
+                false
+            }
+            Event::Char(c) => !c.is_control() && self.insert_str(c.encode_utf8(&mut [0; 4])),
+            Event::Newline => self.multiline && self.insert_str("\n"),
+            Event::Delete(motion) => {
+                let (cursor, anchor) = (self.cursor, self.anchor);
+                if self.selection().is_none() {
+                    self.anchor = Some(self.cursor);
+                    self.cursor = self.target(motion);
+                }
+                let changed = self.insert_str("");
+                if !changed {
+                    self.cursor = cursor;
+                    self.anchor = anchor;
+                }
+                changed
+            }
+            Event::SelectAll => {
+                self.anchor = Some(0);
+                self.cursor = self.len();
+                false
+            }
+        }
+    }
+
+    /// Replace the selection (or insert at the cursor) with `text`, as if it was typed.
+    /// Returns false and leaves the field untouched if the edit is refused
+    pub fn insert_str(&mut self, text: &str) -> bool {
+        let (start, end) = self.selection().unwrap_or((self.cursor, self.cursor));
+        let text = if self.multiline {
+            text.to_owned()
+        } else {
+            text.replace('\n', " ")
+        };
+        if text.is_empty() && start == end {
+            return false;
+        }
+        let mut new_text = String::with_capacity(self.text.len() + text.len());
+        new_text.push_str(&self.text[..self.byte(start)]);
+        new_text.push_str(&text);
+        new_text.push_str(&self.text[self.byte(end)..]);
+        let too_long = self
+            .max_length
+            .is_some_and(|max| new_text.chars().count() > max);
+        // Deleting is always allowed by the length limit, even if the text was already too long
+        let rejected = self
+            .validator
+            .as_ref()
+            .is_some_and(|valid| !valid(&new_text));
+        if (too_long && !text.is_empty()) || rejected {
+            return false;
+        }
+        self.text = new_text;
+        self.cursor = start + text.chars().count();
+        self.anchor = None;
+        true
+    }
+
+    /// Move the cursor, extending the selection if `select` is true or dropping it otherwise
+    pub fn move_cursor(&mut self, motion: Motion, select: bool) {
+        let target = match (self.selection(), motion, select) {
+            // Like most editors, left and right collapse the selection to one of its ends
+            (Some((start, _)), Motion::Left, false) => start,
+            (Some((_, end)), Motion::Right, false) => end,
+            _ => self.target(motion),
+        };
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = target;
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte(&self, index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(index)
+            .map_or(self.text.len(), |(byte, _)| byte)
+    }
+
+    fn line_start(chars: &[char], index: usize) -> usize {
+        chars[..index]
+            .iter()
+            .rposition(|c| *c == '\n')
+            .map_or(0, |i| i + 1)
+    }
+
+    fn line_end(chars: &[char], index: usize) -> usize {
+        chars[index..]
+            .iter()
+            .position(|c| *c == '\n')
+            .map_or(chars.len(), |i| index + i)
+    }
+
+    /// Where the cursor would be after the motion
+    fn target(&self, motion: Motion) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let len = chars.len();
+        let cursor = self.cursor.min(len);
+        match motion {
+            Motion::Left => cursor.saturating_sub(1),
+            Motion::Right => (cursor + 1).min(len),
+            Motion::Up => {
+                let start = Self::line_start(&chars, cursor);
+                if start == 0 {
+                    0
+                } else {
+                    let previous = Self::line_start(&chars, start - 1);
+                    previous + (cursor - start).min(start - 1 - previous)
+                }
+            }
+            Motion::Down => {
+                let start = Self::line_start(&chars, cursor);
+                let end = Self::line_end(&chars, cursor);
+                if end == len {
+                    len
+                } else {
+                    let next_end = Self::line_end(&chars, end + 1);
+                    end + 1 + (cursor - start).min(next_end - end - 1)
+                }
+            }
+            Motion::WordLeft => {
+                let mut i = cursor;
+                while i > 0 && !is_word(chars[i - 1]) {
+                    i -= 1;
+                }
+                while i > 0 && is_word(chars[i - 1]) {
+                    i -= 1;
+                }
+                i
+            }
+            Motion::WordRight => {
+                let mut i = cursor;
+                while i < len && !is_word(chars[i]) {
+                    i += 1;
+                }
+                while i < len && is_word(chars[i]) {
+                    i += 1;
+                }
+                i
+            }
+            Motion::LineStart => Self::line_start(&chars, cursor),
+            Motion::LineEnd => Self::line_end(&chars, cursor),
+            Motion::TextStart => 0,
+            Motion::TextEnd => len,
+            Motion::Index(index) => index.min(len),
+        }
+    }
+
+    /// The lines of the text, with the character index of their start, ready to be drawn with
+    /// the 8x8 font
+    fn display_lines(&self) -> Vec<(usize, String)> {
+        let mut start = 0;
+        self.text
+            .split('\n')
+            .map(|line| {
+                let shown: String = line
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii() && !c.is_control() {
+                            c
+                        } else {
+                            '?'
+                        }
+                    })
+                    .collect();
+                let line_start = start;
+                start += shown.chars().count() + 1;
+                (line_start, shown)
+            })
+            .collect()
+    }
+
+    /// Split a line in its unselected, selected and unselected parts, as column ranges
+    fn split_line(&self, line_start: usize, line_len: usize) -> [(usize, usize); 3] {
+        let (start, end) = self.selection().unwrap_or((0, 0));
+        let sel_start = start.saturating_sub(line_start).min(line_len);
+        let sel_end = end.saturating_sub(line_start).min(line_len);
+        [(0, sel_start), (sel_start, sel_end), (sel_end, line_len)]
+    }
+
+    /// The line and column of the cursor
+    fn cursor_position(&self, lines: &[(usize, String)]) -> (usize, usize) {
+        lines
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, (start, _))| *start <= self.cursor)
+            .map_or((0, 0), |(line, (start, _))| (line, self.cursor - start))
+    }
+
+    /// Draw the field with [`ShapesTrait::draw_text`], its top left corner at `pos`
+    pub fn draw<T: ShapesTrait>(
+        &self,
+        target: &mut T,
+        pos: impl Into<Vi2d>,
+        scale: u32,
+        style: &Style,
+    ) {
+        #![allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let pos = pos.into();
+        let scale = scale.max(1);
+        let glyph = 8 * scale as i32;
+        let lines = self.display_lines();
+        for (row, (line_start, line)) in lines.iter().enumerate() {
+            let y = pos.y + row as i32 * glyph;
+            let columns: Vec<char> = line.chars().collect();
+            for (i, (from, to)) in self
+                .split_line(*line_start, columns.len())
+                .into_iter()
+                .enumerate()
+            {
+                if from == to {
+                    continue;
+                }
+                let x = pos.x + from as i32 * glyph;
+                let color = if i == 1 {
+                    target.fill_rect(
+                        Vi2d { x, y },
+                        Vi2d {
+                            x: (to - from) as i32 * glyph,
+                            y: glyph - 1,
+                        },
+                        style.selection,
+                    );
+                    style.selected_text
+                } else {
+                    style.text
+                };
+                let part: String = columns[from..to].iter().collect();
+                target.draw_text(Vi2d { x, y }, scale, color, &part);
+            }
+        }
+        if let Some(color) = style.cursor {
+            let (row, column) = self.cursor_position(&lines);
+            let x = pos.x + column as i32 * glyph;
+            let y = pos.y + row as i32 * glyph;
+            target.draw_line(
+                Vi2d { x, y },
+                Vi2d {
+                    x,
+                    y: y + glyph - 1,
+                },
+                color,
+            );
+        }
+    }
+
+    /// Draw the field with [`DecalText::draw_text_decal`], its top left corner at `pos`
+    ///
+    /// The background of the selection is `fill`, a 1x1 white decal scaled and tinted with
+    /// [`Style::selection`]. The cursor is drawn as an underscore
+    pub fn draw_decal<T: DecalText>(
+        &self,
+        target: &mut T,
+        pos: impl Into<Vf2d>,
+        scale: impl Into<Vf2d>,
+        fill: &Decal,
+        style: &Style,
+    ) {
+        #![allow(clippy::cast_precision_loss)]
+        let pos = pos.into();
+        let scale = scale.into();
+        let (glyph_w, glyph_h) = (8.0 * scale.x, 8.0 * scale.y);
+        let lines = self.display_lines();
+        for (row, (line_start, line)) in lines.iter().enumerate() {
+            let y = pos.y + row as f32 * glyph_h;
+            let columns: Vec<char> = line.chars().collect();
+            for (i, (from, to)) in self
+                .split_line(*line_start, columns.len())
+                .into_iter()
+                .enumerate()
+            {
+                if from == to {
+                    continue;
+                }
+                let x = pos.x + from as f32 * glyph_w;
+                let color = if i == 1 {
+                    target.draw_decal_scaled_tinted(
+                        Vf2d { x, y },
+                        fill,
+                        Vf2d {
+                            x: (to - from) as f32 * glyph_w,
+                            y: glyph_h - scale.y,
+                        },
+                        style.selection,
+                    );
+                    style.selected_text
+                } else {
+                    style.text
+                };
+                let part: String = columns[from..to].iter().collect();
+                target.draw_text_decal(Vf2d { x, y }, part, scale, color);
+            }
+        }
+        if let Some(color) = style.cursor {
+            let (row, column) = self.cursor_position(&lines);
+            let cursor = Vf2d {
+                x: pos.x + column as f32 * glyph_w,
+                y: pos.y + row as f32 * glyph_h,
+            };
+            target.draw_text_decal(cursor, "_", scale, color);
+        }
+    }
+}
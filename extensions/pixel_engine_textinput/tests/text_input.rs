@@ -0,0 +1,255 @@
+use pixel_engine::inputs::{InputEvent, Keycodes};
+use pixel_engine::testing::{run_frames, InputScript};
+use pixel_engine::traits::*;
+use pixel_engine::{Color, Sprite};
+use pixel_engine_textinput::{EngineSource, Event, Motion, Style, TextInput};
+
+fn type_str(field: &mut TextInput, text: &str) {
+    for c in text.chars() {
+        field.handle_event(Event::Char(c));
+    }
+}
+
+fn moved(motion: Motion, select: bool) -> Event {
+    Event::Move { motion, select }
+}
+
+#[test]
+fn typing_and_deleting() {
+    let mut field = TextInput::new();
+    type_str(&mut field, "héllo wörld");
+    assert_eq!(field.cursor(), 11);
+    field.handle_event(Event::Delete(Motion::Left));
+    assert_eq!(field.text(), "héllo wörl");
+    field.handle_event(moved(Motion::TextStart, false));
+    field.handle_event(Event::Delete(Motion::Right));
+    assert_eq!(field.text(), "éllo wörl");
+    field.handle_event(moved(Motion::TextEnd, false));
+    field.handle_event(Event::Delete(Motion::WordLeft));
+    assert_eq!(field.text(), "éllo ");
+    field.handle_event(Event::Newline);
+    assert_eq!(field.text(), "éllo ");
+}
+
+#[test]
+fn selection() {
+    let mut field = TextInput::new().with_text("one two three");
+    field.handle_event(moved(Motion::WordLeft, true));
+    assert_eq!(field.selected_text(), "three");
+    field.handle_event(moved(Motion::WordLeft, true));
+    assert_eq!(field.selection(), Some((4, 13)));
+    type_str(&mut field, "2");
+    assert_eq!(field.text(), "one 2");
+    field.handle_event(Event::SelectAll);
+    field.handle_event(moved(Motion::Left, false));
+    assert_eq!((field.cursor(), field.selection()), (0, None));
+    field.handle_event(moved(Motion::LineEnd, true));
+    field.handle_event(Event::Delete(Motion::Left));
+    assert_eq!(field.text(), "");
+}
+
+#[test]
+fn word_jumps() {
+    let mut field = TextInput::new().with_text("let x_1 = foo(bar);");
+    let mut stops = Vec::new();
+    field.handle_event(moved(Motion::TextStart, false));
+    for _ in 0..5 {
+        field.handle_event(moved(Motion::WordRight, false));
+        stops.push(field.cursor());
+    }
+    assert_eq!(stops, vec![3, 7, 13, 17, 19]);
+    field.handle_event(moved(Motion::WordLeft, false));
+    assert_eq!(field.cursor(), 14);
+}
+
+#[test]
+fn multiline_navigation() {
+    let mut field = TextInput::new().multiline(true);
+    type_str(&mut field, "abcdef");
+    field.handle_event(Event::Newline);
+    type_str(&mut field, "gh");
+    field.handle_event(Event::Newline);
+    type_str(&mut field, "ijklm");
+    assert_eq!(field.text(), "abcdef\ngh\nijklm");
+    field.handle_event(moved(Motion::Up, false));
+    assert_eq!(field.cursor(), 9);
+    field.handle_event(moved(Motion::Up, false));
+    assert_eq!(field.cursor(), 2);
+    field.handle_event(moved(Motion::Down, false));
+    field.handle_event(moved(Motion::Down, false));
+    assert_eq!(field.cursor(), 12);
+    field.handle_event(moved(Motion::LineStart, false));
+    assert_eq!(field.cursor(), 10);
+    field.handle_event(moved(Motion::Up, false));
+    field.handle_event(moved(Motion::LineEnd, false));
+    assert_eq!(field.cursor(), 9);
+    field.handle_event(moved(Motion::Down, false));
+    field.handle_event(moved(Motion::Down, false));
+    assert_eq!(field.cursor(), 15);
+}
+
+#[test]
+fn max_length_and_validator() {
+    let mut field = TextInput::new()
+        .max_length(Some(4))
+        .validator(|text| text.chars().all(|c| c.is_ascii_digit()));
+    type_str(&mut field, "12a345");
+    assert_eq!(field.text(), "1234");
+    field.handle_event(Event::SelectAll);
+    assert!(field.handle_event(Event::Char('9')));
+    assert_eq!(field.text(), "9");
+    assert!(!field.insert_str("x"));
+    assert!(!field.insert_str("1234"));
+    assert_eq!(field.text(), "9");
+}
+
+#[test]
+fn engine_source() {
+    let script = InputScript::new()
+        .at(1, InputEvent::Character('a'))
+        .at(1, InputEvent::Character('é'))
+        .at(1, InputEvent::Character('b'))
+        .key_tap(2, 1, Keycodes::Left)
+        .at(3, InputEvent::KeyPress(Keycodes::LShift))
+        .key_tap(3, 1, Keycodes::Home)
+        .at(4, InputEvent::KeyRelease(Keycodes::LShift))
+        .at(5, InputEvent::Character('Z'))
+        .key_tap(6, 40, Keycodes::Back);
+    let mut field = TextInput::new().with_text("xyz");
+    let mut source = EngineSource::new();
+    let mut seen = Vec::new();
+    run_frames((8, 8), 8, &script, |engine| {
+        source.update(engine);
+        field.update(&mut source);
+        seen.push((field.text().to_owned(), field.cursor(), field.selection()));
+        Ok(true)
+    })
+    .unwrap();
+    assert_eq!(
+        seen[..6],
+        [
+            ("xyz".to_owned(), 3, None),
+            ("xyzaéb".to_owned(), 6, None),
+            ("xyzaéb".to_owned(), 5, None),
+            ("xyzaéb".to_owned(), 0, Some((0, 5))),
+            ("xyzaéb".to_owned(), 0, Some((0, 5))),
+            ("Zb".to_owned(), 1, None),
+        ]
+    );
+    assert_eq!(seen[6].0, "b");
+}
+
+#[test]
+fn key_repeat() {
+    // The system repeats the key while it is held, as presses of a key already down
+    let script = InputScript::new()
+        .key_tap(1, 10, Keycodes::Left)
+        .at(5, InputEvent::KeyPress(Keycodes::Left))
+        .at(7, InputEvent::KeyPress(Keycodes::Left));
+    let mut field = TextInput::new().with_text(&"x".repeat(40));
+    let mut source = EngineSource::new();
+    let mut cursors = Vec::new();
+    run_frames((8, 8), 12, &script, |engine| {
+        source.update(engine);
+        field.update(&mut source);
+        cursors.push(field.cursor());
+        Ok(true)
+    })
+    .unwrap();
+    // One move on the press and one per repeat, holding the key alone doesn't move
+    assert_eq!(cursors[1], 39);
+    assert_eq!(cursors[4], 39);
+    assert_eq!(cursors[5], 38);
+    assert_eq!(cursors[6], 38);
+    assert_eq!(cursors[7], 37);
+    assert_eq!(cursors[11], 37);
+}
+
+#[test]
+fn events_keep_their_order_within_a_frame() {
+    let script = InputScript::new()
+        .at(1, InputEvent::Character('a'))
+        .at(1, InputEvent::Character('b'))
+        .at(1, InputEvent::KeyPress(Keycodes::Back))
+        .at(1, InputEvent::KeyPress(Keycodes::Back))
+        .at(1, InputEvent::KeyPress(Keycodes::Back))
+        .at(1, InputEvent::KeyRelease(Keycodes::Back))
+        .at(1, InputEvent::Character('c'));
+    let mut field = TextInput::new().with_text("xy");
+    let mut source = EngineSource::new();
+    run_frames((8, 8), 3, &script, |engine| {
+        source.update(engine);
+        field.update(&mut source);
+        Ok(true)
+    })
+    .unwrap();
+    // The two repeats of the same frame delete a character each
+    assert_eq!(field.text(), "xc");
+}
+
+#[test]
+fn draw_with_selection_and_cursor() {
+    let mut field = TextInput::new().with_text("ab");
+    field.handle_event(moved(Motion::Left, true));
+    let style = Style {
+        text: Color::RED,
+        selected_text: Color::BLUE,
+        selection: Color::GREEN,
+        cursor: Some(Color::WHITE),
+    };
+    let frame = run_frames((24, 8), 1, &InputScript::new(), |engine| {
+        engine.clear(Color::BLACK);
+        field.draw(engine, (0, 0), 1, &style);
+        Ok(true)
+    })
+    .unwrap();
+    let colors = |x0: u32, x1: u32| -> Vec<Color> {
+        let mut colors = Vec::new();
+        for x in x0..x1 {
+            for y in 0..8 {
+                let c = frame.get_pixel(x, y);
+                if !colors.contains(&c) {
+                    colors.push(c);
+                }
+            }
+        }
+        colors
+    };
+    assert!(colors(0, 8).contains(&Color::RED));
+    assert!(!colors(0, 8).contains(&Color::GREEN));
+    assert!(colors(9, 16).contains(&Color::GREEN));
+    assert!(colors(9, 16).contains(&Color::BLUE));
+    assert_eq!(colors(8, 9), vec![Color::WHITE]);
+    assert_eq!(colors(16, 24), vec![Color::BLACK]);
+}
+
+#[test]
+fn draw_decal_with_selection() {
+    let mut field = TextInput::new().with_text("ab");
+    field.handle_event(moved(Motion::Left, true));
+    let style = Style {
+        cursor: None,
+        ..Style::default()
+    };
+    let mut screenshot = None;
+    run_frames((24, 8), 1, &InputScript::new(), |engine| {
+        engine.clear(Color::BLUE);
+        let fill = engine.create_decal(&Sprite::new_with_color(1, 1, Color::WHITE));
+        field.draw_decal(engine, (0.0, 0.0), (1.0, 1.0), &fill, &style);
+        screenshot = Some(engine.screenshot());
+        engine.destroy_decal(&fill);
+        Ok(true)
+    })
+    .unwrap();
+    let screenshot = screenshot.unwrap();
+    // The selected "b" is black text on a white background, the rest of the field is untouched
+    let selected: Vec<Color> = (8..16)
+        .flat_map(|x| (0..7).map(move |y| (x, y)))
+        .map(|(x, y)| screenshot.get_pixel(x, y))
+        .collect();
+    assert!(selected.contains(&Color::WHITE));
+    assert!(selected.contains(&Color::BLACK));
+    assert!(!selected.contains(&Color::BLUE));
+    assert_eq!(screenshot.get_pixel(12, 7), Color::BLUE);
+    assert_eq!(screenshot.get_pixel(20, 4), Color::BLUE);
+}
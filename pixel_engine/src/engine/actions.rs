@@ -0,0 +1,160 @@
+//! Named actions bound to inputs, so the controls can be changed without touching the game.
+//!
+//! An [`ActionMap`] binds names to [`Binding`]s, either as buttons queried with
+//! [`Engine::get_action`](super::Engine::get_action) (with the usual pressed/held/released
+//! [`Input`](super::inputs::Input)), or as axes queried with
+//! [`Engine::get_axis`](super::Engine::get_axis) and
+//! [`Engine::get_axis_2d`](super::Engine::get_axis_2d).
+//!
+//! ```no_run
+//! use pixel_engine::actions::{ActionMap, Binding};
+//! use pixel_engine::inputs::{Keycodes, MouseBtn};
+//!
+//! pixel_engine::launch(async move {
+//!     let mut game = pixel_engine::EngineWrapper::new("Actions".to_owned(), (64, 64, 4)).await;
+//!     let defaults = ActionMap::new()
+//!         .bind("jump", Binding::Key(Keycodes::Space))
+//!         .bind("shoot", Binding::Mouse(MouseBtn::Left))
+//!         .bind_axis_2d(
+//!             "move",
+//!             [Keycodes::A, Keycodes::D, Keycodes::W, Keycodes::S].map(Binding::Key),
+//!         )
+//!         .bind_axis_2d(
+//!             "move",
+//!             [Keycodes::Left, Keycodes::Right, Keycodes::Up, Keycodes::Down].map(Binding::Key),
+//!         );
+//!     game.set_actions(ActionMap::load_from_file("controls.ron").unwrap_or(defaults));
+//!     game.run(|engine| {
+//!         let (dx, dy) = engine.get_axis_2d("move");
+//!         if engine.get_action("jump").pressed {
+//!             // ...
+//!         }
+//!         Ok(true)
+//!     });
+//! });
+//! ```
+
+use super::gamepad::{GamepadAxis, GamepadButton};
+use super::inputs::{Keycodes, MouseBtn, MouseWheel};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// An input an action can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    /// A key of the keyboard
+    Key(Keycodes),
+    /// A mouse button
+    Mouse(MouseBtn),
+    /// A direction of the scroll wheel, it is only ever pressed, on the frames it is scrolled
+    Wheel(MouseWheel),
+    /// A button of any connected gamepad
+    GamepadButton(GamepadButton),
+    /// Half of an axis of any connected gamepad, the values above 0 if `positive` or the values
+    /// below 0 otherwise. It is analog in axis actions, and down past half way in button actions
+    GamepadAxis {
+        /// The axis
+        axis: GamepadAxis,
+        /// Which half of the axis
+        positive: bool,
+    },
+}
+
+/// An axis going from -1 when one of the `negative` bindings is down to 1 when one of the
+/// `positive` ones is
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Axis {
+    /// The bindings pulling the axis towards -1
+    pub negative: Vec<Binding>,
+    /// The bindings pulling the axis towards 1
+    pub positive: Vec<Binding>,
+}
+
+/// Two axes, `x` going left (-1) to right (1) and `y` going up (-1) to down (1) like the screen
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Axis2d {
+    /// The horizontal axis
+    pub x: Axis,
+    /// The vertical axis, down is positive
+    pub y: Axis,
+}
+
+/// Named buttons and axes with their bindings, see the [module documentation](self)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionMap {
+    /// The button actions
+    pub buttons: BTreeMap<String, Vec<Binding>>,
+    /// The 1D axes
+    pub axes: BTreeMap<String, Axis>,
+    /// The 2D axes
+    pub axes_2d: BTreeMap<String, Axis2d>,
+}
+
+impl ActionMap {
+    /// Create a map without any action
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a binding to the button `name`
+    #[must_use]
+    pub fn bind<S: Into<String>>(mut self, name: S, binding: Binding) -> Self {
+        self.buttons.entry(name.into()).or_default().push(binding);
+        self
+    }
+
+    /// Add a pair of bindings to the axis `name`
+    #[must_use]
+    pub fn bind_axis<S: Into<String>>(
+        mut self,
+        name: S,
+        negative: Binding,
+        positive: Binding,
+    ) -> Self {
+        let axis = self.axes.entry(name.into()).or_default();
+        axis.negative.push(negative);
+        axis.positive.push(positive);
+        self
+    }
+
+    /// Add bindings to the 2D axis `name`, in the left, right, up, down order
+    #[must_use]
+    pub fn bind_axis_2d<S: Into<String>>(mut self, name: S, bindings: [Binding; 4]) -> Self {
+        let [left, right, up, down] = bindings;
+        let axis = self.axes_2d.entry(name.into()).or_default();
+        axis.x.negative.push(left);
+        axis.x.positive.push(right);
+        axis.y.negative.push(up);
+        axis.y.positive.push(down);
+        self
+    }
+
+    /// Replace the bindings of the button `name`, typically with the one returned by
+    /// [`Engine::get_pressed_binding`](super::Engine::get_pressed_binding) in a controls menu
+    pub fn rebind<S: Into<String>>(&mut self, name: S, bindings: Vec<Binding>) {
+        self.buttons.insert(name.into(), bindings);
+    }
+
+    /// Load a map saved with [`ActionMap::save_to_file`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or isn't a valid map
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        ron::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    /// Save the map as a RON file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,205 @@
+use super::{EngineWrapper, Sprite};
+pub use px_backend::{PresentMode, Scaling};
+
+/// How the window is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowMode {
+    /// A normal window, with decorations
+    Windowed,
+    /// A window without decorations (title bar, borders...)
+    Borderless,
+    /// A window without decorations covering the whole current monitor
+    BorderlessFullscreen,
+    /// Exclusive fullscreen, using the biggest video mode of the current monitor
+    Fullscreen,
+}
+
+/// What the engine does while the window is unfocused, minimised or suspended, see
+/// [`Engine::is_in_background`](super::Engine::is_in_background)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnfocusedMode {
+    /// Keep running as usual
+    Run,
+    /// Stop updating until the window is back, `elapsed` doesn't count the pause. One last frame
+    /// runs first, so the game gets the event that paused it and the releases of its held inputs
+    Pause,
+    /// Keep updating, at most at the given number of frames per second
+    Throttle(u32),
+}
+
+/// Configure an [`Engine`](super::Engine) before creating it
+///
+/// ```no_run
+/// use pixel_engine::{EngineBuilder, WindowMode};
+///
+/// pixel_engine::launch(async move {
+///     let wrapper = EngineBuilder::new("Configured", (160, 90))
+///         .pixel_size(4)
+///         .resizable(true)
+///         .window_mode(WindowMode::Windowed)
+///         .vsync(false)
+///         .max_fps(Some(144))
+///         .build()
+///         .await;
+///     wrapper.run(|_engine| Ok(true));
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct EngineBuilder {
+    pub(crate) title: String,
+    pub(crate) size: (u32, u32),
+    pub(crate) pixel_size: u32,
+    pub(crate) resizable: bool,
+    pub(crate) window_mode: WindowMode,
+    pub(crate) present_mode: PresentMode,
+    pub(crate) scaling: Scaling,
+    pub(crate) position: Option<(i32, i32)>,
+    pub(crate) icon: Option<Sprite>,
+    pub(crate) max_fps: Option<u32>,
+    pub(crate) unfocused_mode: UnfocusedMode,
+    pub(crate) capture: bool,
+}
+
+impl EngineBuilder {
+    /// Start configuring an engine with the given title and size (in pixel)
+    ///
+    /// By default the pixels are 1x1, the window isn't resizable, vsync is on, the screen is
+    /// scaled by whole numbers, the frame rate isn't capped, the game keeps running when the
+    /// window is unfocused and the screenshots don't have the decals
+    #[must_use]
+    pub fn new<S: Into<String>>(title: S, size: (u32, u32)) -> Self {
+        Self {
+            title: title.into(),
+            size,
+            pixel_size: 1,
+            resizable: false,
+            window_mode: WindowMode::Windowed,
+            present_mode: PresentMode::Fifo,
+            scaling: Scaling::Integer,
+            position: None,
+            icon: None,
+            max_fps: None,
+            unfocused_mode: UnfocusedMode::Run,
+            capture: false,
+        }
+    }
+
+    /// Set the title of the window
+    #[must_use]
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Set the size of the screen, in pixel
+    #[must_use]
+    pub fn size(mut self, size: (u32, u32)) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the size of a pixel on the window
+    #[must_use]
+    pub fn pixel_size(mut self, pixel_size: u32) -> Self {
+        self.pixel_size = pixel_size.max(1);
+        self
+    }
+
+    /// Allow the window to be resized by the user
+    #[must_use]
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Set how the window is shown
+    #[must_use]
+    pub fn window_mode(mut self, window_mode: WindowMode) -> Self {
+        self.window_mode = window_mode;
+        self
+    }
+
+    /// Turn vsync on ([`PresentMode::Fifo`]) or off ([`PresentMode::AutoNoVsync`])
+    #[must_use]
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.present_mode = if vsync {
+            PresentMode::Fifo
+        } else {
+            PresentMode::AutoNoVsync
+        };
+        self
+    }
+
+    /// Choose precisely how the frames are synced with the screen, unsupported modes fall back
+    /// to [`PresentMode::Fifo`]
+    #[must_use]
+    pub fn present_mode(mut self, present_mode: PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+
+    /// Set how the screen is scaled when the window is resized or fullscreen
+    #[must_use]
+    pub fn scaling(mut self, scaling: Scaling) -> Self {
+        self.scaling = scaling;
+        self
+    }
+
+    /// Set the initial position of the window on the desktop, in physical pixels
+    #[must_use]
+    pub fn position(mut self, position: (i32, i32)) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Use the sprite as the window's icon
+    #[must_use]
+    pub fn icon(mut self, icon: &Sprite) -> Self {
+        self.icon = Some(icon.clone());
+        self
+    }
+
+    /// Wait between frames so there are at most `max_fps` frames per second, `None` to run as
+    /// fast as possible
+    #[must_use]
+    pub fn max_fps(mut self, max_fps: Option<u32>) -> Self {
+        self.max_fps = max_fps.filter(|fps| *fps > 0);
+        self
+    }
+
+    /// Set what the engine does while the window is unfocused, minimised or suspended
+    #[must_use]
+    pub fn unfocused_mode(mut self, unfocused_mode: UnfocusedMode) -> Self {
+        self.unfocused_mode = unfocused_mode;
+        self
+    }
+
+    /// Include the decals in the screenshots and recordings of the window, see
+    /// [`Engine::screenshot`](super::Engine::screenshot). It keeps a copy of every decal on the
+    /// CPU, an headless engine always does
+    #[must_use]
+    pub fn capture(mut self, capture: bool) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Create the Engine and the Wrapper
+    pub async fn build(self) -> EngineWrapper {
+        EngineWrapper::from_builder(self).await
+    }
+
+    /// Create the Engine and the Wrapper but sync
+    #[cfg(not(target_arch = "wasm32"))]
+    #[must_use]
+    pub fn build_sync(self) -> EngineWrapper {
+        futures::executor::block_on(self.build())
+    }
+
+    /// Create an Engine without window, see [`EngineWrapper::new_headless`]. The options of the
+    /// window are only kept to be read back, the others are kept even though an headless engine
+    /// never waits between frames nor pauses
+    #[must_use]
+    pub fn build_headless(self) -> EngineWrapper {
+        EngineWrapper::from_builder_headless(self)
+    }
+}
@@ -21,7 +21,7 @@ impl Drop for Decal {
 }
 
 impl Decal {
-    pub(crate) fn new(ctx: &mut px_backend::Context, spr: &px_draw::graphics::Sprite) -> Self {
+    pub(crate) fn new(ctx: &mut super::handler::Handler, spr: &px_draw::graphics::Sprite) -> Self {
         let (raw, _lock) = spr.get_read_lock();
         Decal(
             std::mem::ManuallyDrop::new(ctx.create_decal((raw, (spr.width(), spr.height())))),
@@ -33,6 +33,12 @@ impl Decal {
         Decal(self.0.clone(), self.1.clone())
     }
 
+    /// Free the texture of the decal and mark it as destroyed
+    pub(crate) fn destroy(&self, ctx: &mut super::handler::Handler) {
+        ctx.destroy_decal(&self.0);
+        self.1.set(false);
+    }
+
     /// Get the size of the decal in pixel
     #[must_use]
     pub fn size(&self) -> (u32, u32) {
@@ -344,8 +350,9 @@ impl DecalDraw for crate::Engine {
         if decal.is_valid() {
             let pos: [Vf2d; 4] = [pos[0].into(), pos[1].into(), pos[2].into(), pos[3].into()];
             let uv: [Vf2d; 4] = [uv[0].into(), uv[1].into(), uv[2].into(), uv[3].into()];
-            let mut di = px_backend::decals::DecalInstances {
+            let mut di = decals::DecalInstances {
                 id: decal.0.id(),
+                layer: self.draw_target.0,
                 pos: [(0.0, 0.0), (0.0, 0.0), (0.0, 0.0), (0.0, 0.0)],
                 uv: [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
                 w: [1.0; 4],
@@ -483,19 +490,19 @@ impl DecalDraw for crate::Engine {
                 },
                 screen_size
             );
-            self.handler
-                .draw_decal_instance(px_backend::decals::DecalInstances {
-                    id: decal.0.id(),
-                    pos: [
-                        (topleft.x, topleft.y),         // A
-                        (topleft.x, bottomright.y),     // B
-                        (bottomright.x, bottomright.y), // C
-                        (bottomright.x, topleft.y),     // D
-                    ],
-                    uv: [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
-                    w: [1.0; 4],
-                    tint: tint.into(),
-                });
+            self.handler.draw_decal_instance(decals::DecalInstances {
+                id: decal.0.id(),
+                layer: self.draw_target.0,
+                pos: [
+                    (topleft.x, topleft.y),         // A
+                    (topleft.x, bottomright.y),     // B
+                    (bottomright.x, bottomright.y), // C
+                    (bottomright.x, topleft.y),     // D
+                ],
+                uv: [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
+                w: [1.0; 4],
+                tint: tint.into(),
+            });
         }
     }
 
@@ -519,20 +526,20 @@ impl DecalDraw for crate::Engine {
                 },
                 screen_size
             );
-            self.handler
-                .draw_decal_instance(px_backend::decals::DecalInstances {
-                    id: decal.0.id(),
-                    pos: [
-                        (topleft.x, topleft.y),         // A
-                        (topleft.x, bottomright.y),     // B
-                        (bottomright.x, bottomright.y), // C
-                        (bottomright.x, topleft.y),     // D
-                    ],
-                    uv: [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
-                    w: [1.0; 4],
+            self.handler.draw_decal_instance(decals::DecalInstances {
+                id: decal.0.id(),
+                layer: self.draw_target.0,
+                pos: [
+                    (topleft.x, topleft.y),         // A
+                    (topleft.x, bottomright.y),     // B
+                    (bottomright.x, bottomright.y), // C
+                    (bottomright.x, topleft.y),     // D
+                ],
+                uv: [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
+                w: [1.0; 4],
 
-                    tint: tint.into(),
-                });
+                tint: tint.into(),
+            });
         }
     }
 
@@ -567,19 +574,19 @@ impl DecalDraw for crate::Engine {
             uv[2] = (uv_bottomright.x, uv_bottomright.y);
             uv[3] = (uv_bottomright.x, uv_topleft.y);
 
-            self.handler
-                .draw_decal_instance(px_backend::decals::DecalInstances {
-                    id: decal.0.id(),
-                    pos: [
-                        (topleft.x, topleft.y),         // A
-                        (topleft.x, bottomright.y),     // B
-                        (bottomright.x, bottomright.y), // C
-                        (bottomright.x, topleft.y),     // D
-                    ],
-                    uv,
-                    w: [1.0; 4],
-                    tint: tint.into(),
-                });
+            self.handler.draw_decal_instance(decals::DecalInstances {
+                id: decal.0.id(),
+                layer: self.draw_target.0,
+                pos: [
+                    (topleft.x, topleft.y),         // A
+                    (topleft.x, bottomright.y),     // B
+                    (bottomright.x, bottomright.y), // C
+                    (bottomright.x, topleft.y),     // D
+                ],
+                uv,
+                w: [1.0; 4],
+                tint: tint.into(),
+            });
         }
     }
 
@@ -615,19 +622,19 @@ impl DecalDraw for crate::Engine {
             uv[2] = (uv_bottomright.x, uv_bottomright.y);
             uv[3] = (uv_bottomright.x, uv_topleft.y);
 
-            self.handler
-                .draw_decal_instance(px_backend::decals::DecalInstances {
-                    id: decal.0.id(),
-                    pos: [
-                        (topleft.x, topleft.y),         // A
-                        (topleft.x, bottomright.y),     // B
-                        (bottomright.x, bottomright.y), // C
-                        (bottomright.x, topleft.y),     // D
-                    ],
-                    uv,
-                    w: [1.0; 4],
-                    tint: tint.into(),
-                });
+            self.handler.draw_decal_instance(decals::DecalInstances {
+                id: decal.0.id(),
+                layer: self.draw_target.0,
+                pos: [
+                    (topleft.x, topleft.y),         // A
+                    (topleft.x, bottomright.y),     // B
+                    (bottomright.x, bottomright.y), // C
+                    (bottomright.x, topleft.y),     // D
+                ],
+                uv,
+                w: [1.0; 4],
+                tint: tint.into(),
+            });
         }
     }
 
@@ -652,8 +659,9 @@ impl DecalDraw for crate::Engine {
                 normalize!({ pos[3] }, screen_size),
             ];
             let mut center: Vf2d = (0.0, 0.0).into();
-            let mut di = px_backend::decals::DecalInstances {
+            let mut di = decals::DecalInstances {
                 id: decal.0.id(),
+                layer: self.draw_target.0,
                 pos: [(0.0, 0.0), (0.0, 0.0), (0.0, 0.0), (0.0, 0.0)],
                 uv: [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
                 w: [1.0; 4],
@@ -721,8 +729,9 @@ impl DecalDraw for crate::Engine {
                 normalize!({ pos[3] }, screen_size),
             ];
             let mut center: Vf2d = (0.0, 0.0).into();
-            let mut di = px_backend::decals::DecalInstances {
+            let mut di = decals::DecalInstances {
                 id: decal.0.id(),
+                layer: self.draw_target.0,
                 pos: [(0.0, 0.0), (0.0, 0.0), (0.0, 0.0), (0.0, 0.0)],
                 uv: [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
                 w: [1.0; 4],
@@ -818,6 +827,7 @@ impl DecalDraw for crate::Engine {
             }
             self.handler.draw_decal_instance(decals::DecalInstances {
                 id: decal.0.id(),
+                layer: self.draw_target.0,
                 pos: [
                     (pos_arr[0].x, pos_arr[0].y),
                     (pos_arr[1].x, pos_arr[1].y),
@@ -866,6 +876,7 @@ impl DecalDraw for crate::Engine {
             }
             self.handler.draw_decal_instance(decals::DecalInstances {
                 id: decal.0.id(),
+                layer: self.draw_target.0,
                 pos: [
                     (pos_arr[0].x, pos_arr[0].y),
                     (pos_arr[1].x, pos_arr[1].y),
@@ -922,6 +933,7 @@ impl DecalDraw for crate::Engine {
 
             self.handler.draw_decal_instance(decals::DecalInstances {
                 id: decal.0.id(),
+                layer: self.draw_target.0,
                 pos: [
                     (pos_arr[0].x, pos_arr[0].y),
                     (pos_arr[1].x, pos_arr[1].y),
@@ -980,6 +992,7 @@ impl DecalDraw for crate::Engine {
             }
             self.handler.draw_decal_instance(decals::DecalInstances {
                 id: decal.0.id(),
+                layer: self.draw_target.0,
                 pos: [
                     (pos_arr[0].x, pos_arr[0].y),
                     (pos_arr[1].x, pos_arr[1].y),
@@ -1032,13 +1045,19 @@ impl DecalText for crate::Engine {
                 other if other.is_ascii() => {
                     let ox = (chr as u32 - 32) % 16;
                     let oy = (chr as u32 - 32) / 16;
-                    self.draw_partial_decal_tinted(
+                    self.draw_partial_decal_scaled_tinted(
                         pos,
                         &textsheet_decal,
-                        (px_draw::vector2::Vu2d { x: ox, y: oy }).cast_f32(),
+                        (px_draw::vector2::Vu2d {
+                            x: ox * 8,
+                            y: oy * 8,
+                        })
+                        .cast_f32(),
                         Vf2d { x: 8.0, y: 8.0 },
+                        scale,
                         color,
                     );
+                    pos.x += scale.x * 8.0;
                 }
                 _ => pos.x += scale.x * 8.0,
             }
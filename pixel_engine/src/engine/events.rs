@@ -0,0 +1,61 @@
+//! Everything that happened since the previous frame, in order.
+//!
+//! The polling API ([`Engine::get_key`](super::Engine::get_key),
+//! [`Engine::get_mouse_btn`](super::Engine::get_mouse_btn)...) tells the state at the start of the
+//! frame, [`Engine::events`](super::Engine::events) and [`Game::on_event`](super::Game::on_event)
+//! tell how it got there: the order of the key presses, the characters typed between them, every
+//! position of the mouse...
+//!
+//! ```no_run
+//! use pixel_engine::events::Event;
+//! use pixel_engine::inputs::InputEvent;
+//!
+//! pixel_engine::launch(async move {
+//!     let game = pixel_engine::EngineWrapper::new("Events".to_owned(), (64, 64, 4)).await;
+//!     game.run(|engine| {
+//!         for event in engine.events() {
+//!             match event {
+//!                 Event::Input(InputEvent::KeyPress(key)) => println!("{key:?} down"),
+//!                 Event::Resized(width, height) => println!("Now {width}x{height}"),
+//!                 _ => {}
+//!             }
+//!         }
+//!         Ok(true)
+//!     });
+//! });
+//! ```
+
+use super::inputs::InputEvent;
+
+/// Something that happened to the engine or its window
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// An input. A key pressed again while down (the operating system repeating it) is an
+    /// [`InputEvent::KeyRepeat`]
+    Input(InputEvent),
+    /// The window was resized, to the given size in physical pixels
+    Resized(u32, u32),
+    /// The window gained (`true`) or lost (`false`) the keyboard focus. When it is lost, the keys
+    /// and mouse buttons that were down are released
+    Focused(bool),
+    /// The window was minimised or fully hidden (`true`), or shown again (`false`)
+    Occluded(bool),
+    /// The application was put in the background by the system (on mobile and web)
+    Suspended,
+    /// The application came back from the background
+    Resumed,
+    /// A file is being dragged above the window. When several files are dragged, there is one
+    /// event per file
+    HoveredFile(std::path::PathBuf),
+    /// The files dragged above the window left it without being dropped
+    HoveredFileCancelled,
+    /// A file was dropped on the window, see
+    /// [`Engine::get_dropped_files`](super::Engine::get_dropped_files)
+    DroppedFile(std::path::PathBuf),
+}
+
+impl From<InputEvent> for Event {
+    fn from(input: InputEvent) -> Self {
+        Event::Input(input)
+    }
+}
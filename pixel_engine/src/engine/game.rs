@@ -0,0 +1,95 @@
+//! A trait based alternative to the closure given to [`EngineWrapper::run`](super::EngineWrapper::run)
+
+use super::events::Event;
+use super::Engine;
+
+/// The lifecycle of a game, run with [`EngineWrapper::run_app`](super::EngineWrapper::run_app)
+/// or [`EngineWrapper::run_app_headless`](super::EngineWrapper::run_app_headless)
+///
+/// ```no_run
+/// use pixel_engine::decals::Decal;
+/// use pixel_engine::traits::*;
+/// use pixel_engine::{Engine, Game};
+///
+/// struct Demo {
+///     decal: Option<Decal>,
+/// }
+///
+/// impl Game for Demo {
+///     fn on_user_create(&mut self, engine: &mut Engine) -> Result<bool, Box<dyn std::error::Error>> {
+///         let sprite = pixel_engine::Sprite::new_with_color(8, 8, pixel_engine::Color::RED);
+///         self.decal = Some(engine.create_decal(&sprite));
+///         Ok(true)
+///     }
+///
+///     fn on_user_update(
+///         &mut self,
+///         engine: &mut Engine,
+///         _elapsed: f64,
+///     ) -> Result<bool, Box<dyn std::error::Error>> {
+///         engine.clear(pixel_engine::Color::BLACK);
+///         if let Some(decal) = &self.decal {
+///             engine.draw_decal((4.0, 4.0), decal);
+///         }
+///         Ok(true)
+///     }
+///
+///     fn on_user_destroy(&mut self, engine: &mut Engine) {
+///         if let Some(decal) = self.decal.take() {
+///             engine.destroy_decal(&decal);
+///         }
+///     }
+/// }
+///
+/// pixel_engine::launch(async move {
+///     let wrapper = pixel_engine::EngineWrapper::new("Game".to_owned(), (64, 64, 4)).await;
+///     wrapper.run_app(Demo { decal: None });
+/// });
+/// ```
+pub trait Game {
+    /// Called once before the first frame, returning `Ok(false)` or an error stops the game
+    ///
+    /// # Errors
+    ///
+    /// The error is reported and the game stops
+    fn on_user_create(&mut self, _engine: &mut Engine) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(true)
+    }
+
+    /// Called every frame, returning `Ok(false)` or an error stops the game
+    ///
+    /// # Errors
+    ///
+    /// The error is reported and the game stops
+    fn on_user_update(
+        &mut self,
+        engine: &mut Engine,
+        elapsed: f64,
+    ) -> Result<bool, Box<dyn std::error::Error>>;
+
+    /// Called exactly once when the game stops, whatever the reason: the window was closed, a
+    /// function returned `Ok(false)` or an error.
+    /// This is the place to destroy decals and save things
+    fn on_user_destroy(&mut self, _engine: &mut Engine) {}
+
+    /// Called for every event (inputs, window changes) since the previous frame, in order, just
+    /// before [`Game::on_user_update`]. The same events are given by
+    /// [`Engine::events`](super::Engine::events)
+    fn on_event(&mut self, _engine: &mut Engine, _event: &Event) {}
+}
+
+/// A [`Game`] made of the closure given to [`EngineWrapper::run`](super::EngineWrapper::run)
+pub(crate) struct FnGame<F>(pub(crate) F);
+
+impl<F> Game for FnGame<F>
+where
+    F: FnMut(&mut Engine) -> Result<bool, Box<dyn std::error::Error>>,
+{
+    fn on_user_update(
+        &mut self,
+        engine: &mut Engine,
+        _elapsed: f64,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        (self.0)(engine)
+    }
+}
@@ -0,0 +1,257 @@
+//! Gamepads, read through pluggable [`GamepadBackend`]s.
+//!
+//! The engine doesn't talk to the devices itself: backends added with
+//! [`Engine::add_gamepad_backend`](super::Engine::add_gamepad_backend) are polled at the start of
+//! every frame and their [`GamepadEvent`]s update the state queried with
+//! [`Engine::get_gamepad_button`](super::Engine::get_gamepad_button) and
+//! [`Engine::get_gamepad_axis`](super::Engine::get_gamepad_axis).
+//!
+//! A backend for real devices wraps a crate like `gilrs`:
+//!
+//! ```ignore
+//! #[derive(Debug)]
+//! struct Gilrs(gilrs::Gilrs);
+//!
+//! impl GamepadBackend for Gilrs {
+//!     fn poll(&mut self) -> Vec<GamepadEvent> {
+//!         let mut events = Vec::new();
+//!         while let Some(gilrs::Event { id, event, .. }) = self.0.next_event() {
+//!             let id = usize::from(id);
+//!             events.extend(match event {
+//!                 gilrs::EventType::Connected => Some(GamepadEvent::Connected(id)),
+//!                 gilrs::EventType::ButtonPressed(gilrs::Button::South, _) => {
+//!                     Some(GamepadEvent::ButtonPressed(id, GamepadButton::South))
+//!                 }
+//!                 // ...
+//!                 _ => None,
+//!             });
+//!         }
+//!         events
+//!     }
+//! }
+//! ```
+//!
+//! [`VirtualGamepad`] is a backend driven from code, for tests and on-screen controls.
+
+use super::inputs::Input;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// Identify a gamepad, given by the backend
+pub type GamepadId = usize;
+
+/// A button of a gamepad, named by its position on the pad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    /// Bottom face button (A, Cross)
+    South,
+    /// Right face button (B, Circle)
+    East,
+    /// Top face button (Y, Triangle)
+    North,
+    /// Left face button (X, Square)
+    West,
+    /// Left shoulder button
+    LeftBumper,
+    /// Right shoulder button
+    RightBumper,
+    /// Left trigger, as a button. Its analog value is [`GamepadAxis::LeftTrigger`]
+    LeftTrigger,
+    /// Right trigger, as a button. Its analog value is [`GamepadAxis::RightTrigger`]
+    RightTrigger,
+    /// The select / back / share button
+    Select,
+    /// The start / menu / options button
+    Start,
+    /// The button with the logo of the vendor
+    Mode,
+    /// Clicking the left stick
+    LeftStick,
+    /// Clicking the right stick
+    RightStick,
+    /// Up on the directional pad
+    DPadUp,
+    /// Down on the directional pad
+    DPadDown,
+    /// Left on the directional pad
+    DPadLeft,
+    /// Right on the directional pad
+    DPadRight,
+}
+
+/// An analog axis of a gamepad
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    /// Left stick, from -1 (left) to 1 (right)
+    LeftStickX,
+    /// Left stick, from -1 (up) to 1 (down)
+    LeftStickY,
+    /// Right stick, from -1 (left) to 1 (right)
+    RightStickX,
+    /// Right stick, from -1 (up) to 1 (down)
+    RightStickY,
+    /// Left trigger, from 0 (released) to 1 (fully pressed)
+    LeftTrigger,
+    /// Right trigger, from 0 (released) to 1 (fully pressed)
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    /// Index of the axis inside the `GamepadState::axes` array
+    pub(crate) const fn index(self) -> usize {
+        match self {
+            GamepadAxis::LeftStickX => 0,
+            GamepadAxis::LeftStickY => 1,
+            GamepadAxis::RightStickX => 2,
+            GamepadAxis::RightStickY => 3,
+            GamepadAxis::LeftTrigger => 4,
+            GamepadAxis::RightTrigger => 5,
+        }
+    }
+}
+
+/// Something that happened to a gamepad
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GamepadEvent {
+    /// The gamepad was plugged in
+    Connected(GamepadId),
+    /// The gamepad was unplugged, its state is forgotten
+    Disconnected(GamepadId),
+    /// The button is pressed down
+    ButtonPressed(GamepadId, GamepadButton),
+    /// The button is released
+    ButtonReleased(GamepadId, GamepadButton),
+    /// The axis moved to the given value, dead zones are applied by the engine
+    AxisChanged(GamepadId, GamepadAxis, f32),
+}
+
+/// A source of gamepad events
+pub trait GamepadBackend: std::fmt::Debug {
+    /// Return the events that happened since the last call, in order.
+    /// Called once at the start of every frame
+    fn poll(&mut self) -> Vec<GamepadEvent>;
+}
+
+/// A gamepad driven from code, see the [module documentation](self)
+///
+/// Clones share the same queue, so one can be given to the engine and the other kept to press
+/// buttons
+///
+/// ```
+/// use pixel_engine::gamepad::{GamepadButton, VirtualGamepad};
+///
+/// let mut wrapper = pixel_engine::EngineWrapper::new_headless((8, 8));
+/// let pad = VirtualGamepad::new(0);
+/// wrapper.add_gamepad_backend(pad.clone());
+/// pad.press(GamepadButton::South);
+/// wrapper
+///     .run_headless(1, |engine| {
+///         assert!(engine.get_gamepad_button(0, GamepadButton::South).pressed);
+///         Ok(true)
+///     })
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct VirtualGamepad {
+    id: GamepadId,
+    events: Rc<RefCell<VecDeque<GamepadEvent>>>,
+}
+
+impl VirtualGamepad {
+    /// Create a gamepad with the given id, it is connected on the next poll
+    #[must_use]
+    pub fn new(id: GamepadId) -> Self {
+        let pad = Self {
+            id,
+            events: Rc::default(),
+        };
+        pad.push(GamepadEvent::Connected(id));
+        pad
+    }
+
+    fn push(&self, event: GamepadEvent) {
+        self.events.borrow_mut().push_back(event);
+    }
+
+    /// The id of the gamepad
+    #[must_use]
+    pub fn id(&self) -> GamepadId {
+        self.id
+    }
+
+    /// Press a button
+    pub fn press(&self, button: GamepadButton) {
+        self.push(GamepadEvent::ButtonPressed(self.id, button));
+    }
+
+    /// Release a button
+    pub fn release(&self, button: GamepadButton) {
+        self.push(GamepadEvent::ButtonReleased(self.id, button));
+    }
+
+    /// Move an axis
+    pub fn set_axis(&self, axis: GamepadAxis, value: f32) {
+        self.push(GamepadEvent::AxisChanged(self.id, axis, value));
+    }
+
+    /// Unplug the gamepad
+    pub fn disconnect(&self) {
+        self.push(GamepadEvent::Disconnected(self.id));
+    }
+}
+
+impl GamepadBackend for VirtualGamepad {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        self.events.borrow_mut().drain(..).collect()
+    }
+}
+
+/// The state of a connected gamepad
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GamepadState {
+    pub(crate) buttons: std::collections::HashMap<GamepadButton, Input>,
+    /// The buttons that are down
+    pub(crate) down: std::collections::HashSet<GamepadButton>,
+    pub(crate) axes: [f32; 6],
+    pub(crate) previous_axes: [f32; 6],
+}
+
+impl GamepadState {
+    pub(crate) fn press(&mut self, button: GamepadButton) {
+        if self.down.insert(button) {
+            self.buttons
+                .entry(button)
+                .or_insert_with(Input::default)
+                .pressed = true;
+        }
+    }
+
+    /// Release a button, it stays pressed if it was pressed during the same frame
+    pub(crate) fn release(&mut self, button: GamepadButton) {
+        self.down.remove(&button);
+        let input = self.buttons.entry(button).or_insert_with(Input::default);
+        input.held = false;
+        input.released = true;
+    }
+
+    /// Go from one frame to the next one: the buttons down become held, the others are forgotten
+    pub(crate) fn roll(&mut self) {
+        for (button, input) in &mut self.buttons {
+            input.pressed = false;
+            input.held = self.down.contains(button);
+            input.released = false;
+        }
+        self.previous_axes = self.axes;
+    }
+}
+
+/// Remove the dead zone from an axis value, rescaling the rest to keep the full range
+pub(crate) fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    if value.abs() <= dead_zone {
+        0.0
+    } else {
+        value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone)
+    }
+}
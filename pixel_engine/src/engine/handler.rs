@@ -0,0 +1,76 @@
+use px_backend::decals;
+
+/// The thing that present the frames, either to a window or kept in memory
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum Handler {
+    Window(px_backend::Context),
+    Headless(px_backend::headless::Context),
+}
+
+impl Handler {
+    pub(crate) fn render(&mut self, data: &[u8], layers: &[px_backend::Layer]) {
+        match self {
+            Handler::Window(ctx) => ctx.render_layers(data, layers),
+            Handler::Headless(ctx) => ctx.render_layers(data, layers),
+        }
+    }
+
+    /// The window was resized, its new size is in physical pixels
+    pub(crate) fn resize(&mut self, size: (u32, u32)) {
+        match self {
+            Handler::Window(ctx) => ctx.resize(size),
+            Handler::Headless(_) => {}
+        }
+    }
+
+    pub(crate) fn set_scaling(&mut self, scaling: px_backend::Scaling) {
+        match self {
+            Handler::Window(ctx) => ctx.set_scaling(scaling),
+            Handler::Headless(_) => {}
+        }
+    }
+
+    /// Where the screen is shown, the whole frame when headless
+    pub(crate) fn viewport(&self) -> px_backend::Viewport {
+        match self {
+            Handler::Window(ctx) => ctx.viewport(),
+            Handler::Headless(ctx) => {
+                let (_, (width, height)) = ctx.frame();
+                px_backend::Viewport {
+                    x: 0,
+                    y: 0,
+                    width,
+                    height,
+                }
+            }
+        }
+    }
+
+    pub(crate) fn composite(&self, data: &[u8], layers: &[px_backend::Layer]) -> Vec<u8> {
+        match self {
+            Handler::Window(ctx) => ctx.composite(data, layers),
+            Handler::Headless(ctx) => ctx.composite(data, layers),
+        }
+    }
+
+    pub(crate) fn create_decal(&mut self, spr: (&[u8], (u32, u32))) -> decals::Decal {
+        match self {
+            Handler::Window(ctx) => ctx.create_decal(spr),
+            Handler::Headless(ctx) => ctx.create_decal(spr),
+        }
+    }
+
+    pub(crate) fn destroy_decal(&mut self, decal: &decals::Decal) {
+        match self {
+            Handler::Window(ctx) => decal.destroy(ctx),
+            Handler::Headless(ctx) => ctx.destroy_decal(decal),
+        }
+    }
+
+    pub(crate) fn draw_decal_instance(&mut self, decal_instance: decals::DecalInstances) {
+        match self {
+            Handler::Window(ctx) => ctx.draw_decal_instance(decal_instance),
+            Handler::Headless(ctx) => ctx.draw_decal_instance(decal_instance),
+        }
+    }
+}
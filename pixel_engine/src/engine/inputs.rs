@@ -1,6 +1,7 @@
 use px_backend::winit;
 #[derive(Debug, Clone, Copy)]
 /// A Simple Struct that Represent an Input
+#[allow(clippy::struct_excessive_bools)]
 pub struct Input {
     /// Is the input pressed on that frame
     pub pressed: bool,
@@ -8,6 +9,9 @@ pub struct Input {
     pub held: bool,
     /// Is the input released on that frame
     pub released: bool,
+    /// Did the operating system repeat the key on that frame, because it is held down.
+    /// A repeat doesn't make the key pressed again
+    pub repeated: bool,
 }
 
 impl Input {
@@ -18,8 +22,15 @@ impl Input {
             pressed,
             held,
             released,
+            repeated: false,
         }
     }
+    /// Set the `repeated` field
+    #[must_use]
+    pub const fn with_repeated(mut self, repeated: bool) -> Self {
+        self.repeated = repeated;
+        self
+    }
     /// Create an [`Input`] where all field are set to false
     #[must_use]
     pub const fn default() -> Self {
@@ -27,6 +38,7 @@ impl Input {
             pressed: false,
             held: false,
             released: false,
+            repeated: false,
         }
     }
     /// Return true if any of the field is true, false otherwise
@@ -36,29 +48,30 @@ impl Input {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Default)]
 pub(crate) struct Mouse {
-    /// State of each mouse button
-    /// 0 => None
-    /// 1 => Pressed
-    /// 2 => Held
-    /// 3 => Released
-    pub(crate) buttons: [Input; 3],
-    pub(crate) pos: (u32, u32),
+    /// State of the mouse buttons that were used
+    pub(crate) buttons: std::collections::HashMap<MouseBtn, Input>,
+    /// The buttons that are down
+    pub(crate) down: std::collections::HashSet<MouseBtn>,
+    /// Position on the screen, in pixels
+    pub(crate) pos: (f32, f32),
+    /// Movement on the screen during the frame, in pixels
+    pub(crate) delta: (f32, f32),
+    /// Raw movement of the device during the frame
+    pub(crate) raw_delta: (f32, f32),
     pub(crate) wheel: MouseWheel,
+    /// Scrolling during the frame, in lines
+    pub(crate) scroll: (f32, f32),
+    pub(crate) in_window: bool,
+    /// Is `pos` where the cursor was since it entered the window, the delta starts from it
+    pub(crate) tracked: bool,
 }
 
-impl Mouse {
-    pub const fn new() -> Self {
-        Mouse {
-            buttons: [Input::default(), Input::default(), Input::default()],
-            pos: (0, 0),
-            wheel: MouseWheel::None,
-        }
-    }
-}
 /// Represent a Mouse Button
-#[derive(Debug, Clone, Copy)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
 pub enum MouseBtn {
     /// The left click
     Left,
@@ -66,12 +79,51 @@ pub enum MouseBtn {
     Right,
     /// The left middle click (scroll wheel click)
     Middle,
+    /// The back side button
+    Back,
+    /// The forward side button
+    Forward,
+    /// Another button, numbered by the platform
+    Other(u16),
+}
+
+impl MouseBtn {
+    /// Convert a button given by winit, recognizing the side buttons of each platform
+    pub(crate) fn from_winit(button: winit::event::MouseButton) -> Self {
+        use winit::event::MouseButton;
+        match button {
+            MouseButton::Left => MouseBtn::Left,
+            MouseButton::Right => MouseBtn::Right,
+            MouseButton::Middle => MouseBtn::Middle,
+            #[cfg(target_os = "windows")]
+            MouseButton::Other(1) => MouseBtn::Back,
+            #[cfg(target_os = "windows")]
+            MouseButton::Other(2) => MouseBtn::Forward,
+            #[cfg(target_arch = "wasm32")]
+            MouseButton::Other(0) => MouseBtn::Back,
+            #[cfg(target_arch = "wasm32")]
+            MouseButton::Other(1) => MouseBtn::Forward,
+            #[cfg(target_os = "macos")]
+            MouseButton::Other(3) => MouseBtn::Back,
+            #[cfg(target_os = "macos")]
+            MouseButton::Other(4) => MouseBtn::Forward,
+            // X11 numbers, then Wayland (Linux input) codes
+            #[cfg(not(any(target_os = "windows", target_os = "macos", target_arch = "wasm32")))]
+            MouseButton::Other(8 | 0x113) => MouseBtn::Back,
+            #[cfg(not(any(target_os = "windows", target_os = "macos", target_arch = "wasm32")))]
+            MouseButton::Other(9 | 0x114) => MouseBtn::Forward,
+            MouseButton::Other(n) => MouseBtn::Other(n),
+        }
+    }
 }
 
 /// Represent a scroll wheel Direction
-#[derive(Debug, Clone, Copy)]
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
 pub enum MouseWheel {
     /// No Scroll
+    #[default]
     None,
     /// Scrolling Up
     Up,
@@ -83,7 +135,89 @@ pub enum MouseWheel {
     Left,
 }
 
+impl MouseWheel {
+    /// The main direction of a scroll, see [`InputEvent::MouseScroll`]
+    pub(crate) fn from_scroll(x: f32, y: f32) -> Self {
+        if x.abs() > y.abs() {
+            if x > 0.0 {
+                MouseWheel::Right
+            } else {
+                MouseWheel::Left
+            }
+        } else if y > 0.0 {
+            MouseWheel::Down
+        } else if y < 0.0 {
+            MouseWheel::Up
+        } else {
+            MouseWheel::None
+        }
+    }
+
+    /// The scroll of one line in this direction
+    pub(crate) const fn to_scroll(self) -> (f32, f32) {
+        match self {
+            MouseWheel::None => (0.0, 0.0),
+            MouseWheel::Up => (0.0, -1.0),
+            MouseWheel::Down => (0.0, 1.0),
+            MouseWheel::Right => (1.0, 0.0),
+            MouseWheel::Left => (-1.0, 0.0),
+        }
+    }
+}
+
+/// How the cursor is kept inside the window, see
+/// [`Engine::set_cursor_grab`](super::Engine::set_cursor_grab)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CursorGrab {
+    /// The cursor moves freely
+    None,
+    /// The cursor can't leave the window
+    Confined,
+    /// The cursor stays in place, use the raw motion
+    /// ([`Engine::get_mouse_raw_delta`](super::Engine::get_mouse_raw_delta)) to read the movements.
+    /// Where the platform can't lock it, it is confined instead
+    Locked,
+}
+
 pub use winit::event::VirtualKeyCode as Keycodes;
+pub use winit::event::ModifiersState as Modifiers;
+
+/// An input received by the engine
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum InputEvent {
+    /// The key is pressed down
+    KeyPress(Keycodes),
+    /// The key is released
+    KeyRelease(Keycodes),
+    /// The key is held down and the operating system repeated it.
+    /// A [`InputEvent::KeyPress`] of a key already down is turned into this
+    KeyRepeat(Keycodes),
+    /// The state of the modifier keys (shift, ctrl, alt, logo) changed
+    Modifiers(Modifiers),
+    /// The mouse is moved at the given location (in pixel, with the fraction of pixel)
+    MouseMove(f32, f32),
+    /// The mouse entered the window
+    MouseEntered,
+    /// The mouse left the window
+    MouseLeft,
+    /// The mouse device moved, in unspecified units, even when the cursor is grabbed or outside
+    /// the window
+    MouseMotion(f32, f32),
+    /// The mouse button is pressed down
+    MousePress(MouseBtn),
+    /// The mouse button is released
+    MouseRelease(MouseBtn),
+    /// The scroll wheel is used, it scrolls one line in this direction
+    MouseWheel(MouseWheel),
+    /// The scroll wheel is used, by the given amount of lines horizontally and vertically.
+    /// A positive `x` is [`MouseWheel::Right`] and a positive `y` is [`MouseWheel::Down`]
+    MouseScroll(f32, f32),
+    /// A character was typed, with the keyboard layout, modifiers, dead keys and IME applied.
+    /// Control characters (backspace, enter...) are not sent, use the keys for those
+    Character(char),
+    /// Something happened to a gamepad
+    Gamepad(super::gamepad::GamepadEvent),
+}
 #[derive(Eq, PartialEq, Debug, Hash, Clone, Copy)]
 /// Represent a Key
 pub struct Key {
@@ -0,0 +1,64 @@
+use px_draw::graphics::{Color, DrawingSprite, Sprite};
+use px_draw::vector2::Vf2d;
+
+/// A layer of the screen, created by [`Engine::create_layer`](super::Engine::create_layer).
+///
+/// The layers are drawn over the screen in the order they were created, each one blended with
+/// the alpha of its pixels, and they all have the size of the screen.
+/// Select the layer the drawing functions and the decals go to with
+/// [`Engine::set_draw_target`](super::Engine::set_draw_target)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Layer(pub(crate) usize);
+
+impl Layer {
+    /// The screen itself, below every layer. It can't be moved, tinted or disabled
+    pub const SCREEN: Layer = Layer(0);
+}
+
+/// The content of a [`Layer`] and how it is shown
+#[derive(Debug)]
+pub(crate) struct LayerData {
+    pub(crate) sprite: DrawingSprite<Sprite>,
+    pub(crate) enabled: bool,
+    pub(crate) offset: Vf2d,
+    pub(crate) scale: Vf2d,
+    pub(crate) tint: Color,
+}
+
+impl LayerData {
+    pub(crate) fn new(size: (u32, u32)) -> Self {
+        Self {
+            sprite: DrawingSprite::new(Sprite::new_with_color(size.0, size.1, Color::BLANK)),
+            enabled: true,
+            offset: Vf2d { x: 0.0, y: 0.0 },
+            scale: Vf2d { x: 1.0, y: 1.0 },
+            tint: Color::WHITE,
+        }
+    }
+}
+
+/// Call `f` with the pixels of the screen and the layers as the backend takes them, the sprites
+/// are locked meanwhile
+pub(crate) fn with_frame<R>(
+    screen: &DrawingSprite<Sprite>,
+    layers: &[LayerData],
+    f: impl FnOnce(&[u8], &[px_backend::Layer]) -> R,
+) -> R {
+    let (raw, _readlock) = screen.get_ref().get_read_lock();
+    let locked: Vec<_> = layers
+        .iter()
+        .map(|layer| layer.sprite.get_ref().get_read_lock())
+        .collect();
+    let backend_layers: Vec<_> = layers
+        .iter()
+        .zip(&locked)
+        .map(|(layer, (data, _))| px_backend::Layer {
+            data,
+            enabled: layer.enabled,
+            offset: (layer.offset.x, layer.offset.y),
+            scale: (layer.scale.x, layer.scale.y),
+            tint: layer.tint.into(),
+        })
+        .collect();
+    f(raw, &backend_layers)
+}
@@ -1,14 +1,27 @@
+use super::actions::{ActionMap, Axis, Binding};
+use super::builder::{EngineBuilder, PresentMode, Scaling, UnfocusedMode, WindowMode};
 use super::decals::Decal;
-use super::inputs::{self, Input, KeySet, Mouse, MouseBtn, MouseWheel};
-use super::Sprite;
+use super::events::Event;
+use super::game::{FnGame, Game};
+use super::gamepad::{
+    apply_dead_zone, GamepadAxis, GamepadBackend, GamepadButton, GamepadEvent, GamepadId,
+    GamepadState,
+};
+use super::handler::Handler;
+use super::inputs::{self, Input, InputEvent, KeySet, Mouse, MouseBtn, MouseWheel};
+use super::layer::{self, Layer, LayerData};
+use super::recorder::{Recorder, RecordingFormat};
+use super::timestep::FixedTimestep;
+use super::trace::{FrameInputs, InputTrace};
+use super::{Color, Sprite};
 
 use pixel_engine_draw::traits::SmartDrawingTrait;
 use px_draw::graphics::DrawingSprite;
 
-use pixel_engine_draw::vector2::Vu2d;
+use pixel_engine_draw::vector2::{Vf2d, Vu2d};
 use px_backend::winit::{
     self,
-    event::{Event, WindowEvent},
+    event::{Event as WinitEvent, WindowEvent},
 };
 
 /// A Wrapper around an Engine
@@ -32,161 +45,215 @@ impl std::ops::DerefMut for EngineWrapper {
 
 impl EngineWrapper {
     /// Create the Engine and the Wrapper
+    ///
+    /// See [`EngineBuilder`] for more options
     pub async fn new(title: String, size: (u32, u32, u32)) -> Self {
-        Self(Some(Engine::new(title, size).await))
+        EngineBuilder::new(title, (size.0, size.1))
+            .pixel_size(size.2)
+            .build()
+            .await
     }
     /// Create the Engine and the Wrapper
     #[cfg(not(target_arch = "wasm32"))]
     #[must_use]
     pub fn new_sync(title: String, size: (u32, u32, u32)) -> Self {
-        Self(Some(Engine::new_sync(title, size)))
+        EngineBuilder::new(title, (size.0, size.1))
+            .pixel_size(size.2)
+            .build_sync()
+    }
+    pub(crate) async fn from_builder(builder: EngineBuilder) -> Self {
+        Self(Some(Engine::new(builder).await))
+    }
+    /// Create the Engine and the Wrapper, without any window or GPU
+    ///
+    /// The size is `(x-size, y-size)` in pixel.
+    /// Such an engine can only be run with [`EngineWrapper::run_headless`]
+    #[must_use]
+    pub fn new_headless(size: (u32, u32)) -> Self {
+        EngineBuilder::new("", size).build_headless()
+    }
+    pub(crate) fn from_builder_headless(builder: EngineBuilder) -> Self {
+        Self(Some(Engine::new_headless(builder)))
     }
     /// The core of your program,
     ///
     /// Takes a function F that will be run every frame, It will do the event handling  and similar
     /// things between frames.
-    #[allow(clippy::too_many_lines, clippy::missing_panics_doc)]
-    pub fn run<F>(mut self, mut main_func: F) -> !
+    pub fn run<F>(self, main_func: F) -> !
     where
         F: (FnMut(&mut Engine) -> Result<bool, Box<dyn std::error::Error>>) + 'static,
     {
+        self.run_app(FnGame(main_func))
+    }
+
+    /// Run a [`Game`]: [`Game::on_user_create`] is called once, then [`Game::on_event`] and
+    /// [`Game::on_user_update`] every frame, and [`Game::on_user_destroy`] once the game stops.
+    #[allow(clippy::too_many_lines, clippy::missing_panics_doc)]
+    pub fn run_app<G: Game + 'static>(mut self, mut game: G) -> ! {
         let mut engine = self.0.take().unwrap();
         let mut force_exit = false;
         let event_loop = engine.event_loop.take().unwrap();
         let mut redraw = true;
         let mut redraw_last_frame = false;
+        let mut paused = false;
+        // Did the last frame already run in the background, the game has then been given the
+        // events that paused it and the releases of its held inputs
+        let mut last_frame_paused = false;
+        let mut running = report_stop(game.on_user_create(&mut engine));
+        let mut destroyed = false;
         event_loop.run(move |e, _, control_flow| {
-            if redraw_last_frame {
-                for key in &engine.k_pressed {
-                    engine.k_held.insert(*key);
-                }
-                engine.k_pressed.clear();
-                engine.k_released.clear();
-                for i in 0..3 {
-                    if engine.mouse.buttons[i].released {
-                        engine.mouse.buttons[i].released = false;
-                    }
-                    if engine.mouse.buttons[i].pressed {
-                        engine.mouse.buttons[i].pressed = false;
-                        engine.mouse.buttons[i].held = true;
-                    }
+            if !running {
+                if !destroyed {
+                    game.on_user_destroy(&mut engine);
+                    destroyed = true;
                 }
-                engine.mouse.wheel = MouseWheel::None;
+                *control_flow = winit::event_loop::ControlFlow::Exit;
+                return;
+            }
+            if redraw_last_frame {
+                engine.roll_inputs();
                 redraw_last_frame = false;
             }
             match e {
-                Event::WindowEvent {
+                WinitEvent::WindowEvent {
                     event: e,
                     window_id,
-                } if window_id == engine.window.id() => match e {
+                } if window_id == engine.window().id() => match e {
                     WindowEvent::KeyboardInput { input: inp, .. } => {
                         if let Some(k) = inp.virtual_keycode {
                             if inp.state == winit::event::ElementState::Released {
-                                engine.k_pressed.remove(&inputs::Key::from(inp));
-                                engine.k_held.remove(&inputs::Key::from(inp));
-                                engine.k_released.insert(inputs::Key::from(inp));
-                            } else if !engine.k_held.has(k) {
-                                engine.k_pressed.insert(inputs::Key::from(inp));
+                                engine.handle_input(InputEvent::KeyRelease(k));
+                            } else {
+                                engine.handle_input(InputEvent::KeyPress(k));
                             }
                         }
                     }
+                    WindowEvent::ModifiersChanged(modifiers) => {
+                        engine.handle_input(InputEvent::Modifiers(modifiers));
+                    }
+                    WindowEvent::ReceivedCharacter(c) if !c.is_control() => {
+                        engine.handle_input(InputEvent::Character(c));
+                    }
+                    WindowEvent::Ime(winit::event::Ime::Commit(text)) => {
+                        for c in text.chars().filter(|c| !c.is_control()) {
+                            engine.handle_input(InputEvent::Character(c));
+                        }
+                    }
                     WindowEvent::CloseRequested => {
                         force_exit = true;
                     }
+                    WindowEvent::Resized(size) => {
+                        engine.handler.resize((size.width, size.height));
+                        engine.handle_event(Event::Resized(size.width, size.height));
+                    }
+                    WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                        let size = (new_inner_size.width, new_inner_size.height);
+                        engine.handler.resize(size);
+                        engine.handle_event(Event::Resized(size.0, size.1));
+                    }
+                    WindowEvent::Focused(focused) => {
+                        engine.handle_event(Event::Focused(focused));
+                    }
+                    WindowEvent::Occluded(occluded) => {
+                        engine.handle_event(Event::Occluded(occluded));
+                    }
+                    WindowEvent::HoveredFile(path) => {
+                        engine.handle_event(Event::HoveredFile(path));
+                    }
+                    WindowEvent::HoveredFileCancelled => {
+                        engine.handle_event(Event::HoveredFileCancelled);
+                    }
+                    WindowEvent::DroppedFile(path) => {
+                        engine.handle_event(Event::DroppedFile(path));
+                    }
                     WindowEvent::CursorMoved { position, .. } => {
-                        let (x, y): (f64, f64) = position.into();
-                        //events.push(Events::MouseMove(x, y));
-                        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-                        {
-                            engine.mouse.pos = (
-                                (x / f64::from(engine.size.2)).trunc().abs() as u32,
-                                (y / f64::from(engine.size.2)).trunc().abs() as u32,
-                            );
+                        let screen = (engine.size.0, engine.size.1);
+                        let (x, y) = engine.handler.viewport().to_screen(position.into(), screen);
+                        // On the black bars, the mouse sticks to the closest edge of the screen
+                        #[allow(clippy::cast_possible_truncation)]
+                        engine.handle_input(InputEvent::MouseMove(
+                            x.clamp(0.0, f64::from(screen.0)) as f32,
+                            y.clamp(0.0, f64::from(screen.1)) as f32,
+                        ));
+                    }
+                    WindowEvent::CursorEntered { .. } => {
+                        engine.handle_input(InputEvent::MouseEntered);
+                    }
+                    WindowEvent::CursorLeft { .. } => {
+                        engine.handle_input(InputEvent::MouseLeft);
+                    }
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        #[allow(clippy::cast_possible_truncation)]
+                        let (x, y) = match delta {
+                            winit::event::MouseScrollDelta::LineDelta(x, y) => (x, y),
+                            winit::event::MouseScrollDelta::PixelDelta(lp) => (
+                                (lp.x / PIXELS_PER_LINE) as f32,
+                                (lp.y / PIXELS_PER_LINE) as f32,
+                            ),
                         };
+                        engine.handle_input(InputEvent::MouseScroll(x, y));
                     }
-                    WindowEvent::MouseWheel { delta, .. } => match delta {
-                        winit::event::MouseScrollDelta::LineDelta(x, y) => {
-                            engine.mouse.wheel = if x.abs() > y.abs() {
-                                if x > 0.0 {
-                                    MouseWheel::Right
-                                } else if x < 0.0 {
-                                    MouseWheel::Left
-                                } else {
-                                    MouseWheel::None
-                                }
-                            } else if y > 0.0 {
-                                MouseWheel::Down
-                            } else if y < 0.0 {
-                                MouseWheel::Up
-                            } else {
-                                MouseWheel::None
-                            };
-                        }
-                        winit::event::MouseScrollDelta::PixelDelta(lp) => {
-                            let (x, y): (f64, f64) = lp.into();
-                            engine.mouse.wheel = if x.abs() > y.abs() {
-                                if x > 0.0 {
-                                    MouseWheel::Right
-                                } else if x < 0.0 {
-                                    MouseWheel::Left
-                                } else {
-                                    MouseWheel::None
-                                }
-                            } else if y > 0.0 {
-                                MouseWheel::Down
-                            } else if y < 0.0 {
-                                MouseWheel::Up
-                            } else {
-                                MouseWheel::None
-                            };
-                        }
-                    },
                     WindowEvent::MouseInput { button, state, .. } => {
-                        if std::mem::discriminant(&button)
-                            != std::mem::discriminant(&winit::event::MouseButton::Other(0))
-                        {
-                            let btn = match button {
-                                winit::event::MouseButton::Left => MouseBtn::Left,
-                                winit::event::MouseButton::Right => MouseBtn::Right,
-                                winit::event::MouseButton::Middle => MouseBtn::Middle,
-                                winit::event::MouseButton::Other(_) => {
-                                    unreachable!("MouseButton::Other()")
-                                }
-                            };
-                            if state == winit::event::ElementState::Pressed {
-                                engine.mouse.buttons[match btn {
-                                    MouseBtn::Left => 0,
-                                    MouseBtn::Right => 1,
-                                    MouseBtn::Middle => 2,
-                                }]
-                                .pressed = true;
-                            } else {
-                                engine.mouse.buttons[match btn {
-                                    MouseBtn::Left => 0,
-                                    MouseBtn::Right => 1,
-                                    MouseBtn::Middle => 2,
-                                }]
-                                .released = true;
-                                engine.mouse.buttons[match btn {
-                                    MouseBtn::Left => 0,
-                                    MouseBtn::Right => 1,
-                                    MouseBtn::Middle => 2,
-                                }]
-                                .held = false;
-                            }
+                        let btn = MouseBtn::from_winit(button);
+                        if state == winit::event::ElementState::Pressed {
+                            engine.handle_input(InputEvent::MousePress(btn));
+                        } else {
+                            engine.handle_input(InputEvent::MouseRelease(btn));
                         }
                     }
                     _ => {}
                 },
-                Event::RedrawRequested(_) => {
+                WinitEvent::DeviceEvent {
+                    event: winit::event::DeviceEvent::MouseMotion { delta: (x, y) },
+                    ..
+                } => {
+                    #[allow(clippy::cast_possible_truncation)]
+                    engine.handle_input(InputEvent::MouseMotion(x as f32, y as f32));
+                }
+                WinitEvent::Suspended => {
+                    engine.handle_event(Event::Suspended);
+                }
+                WinitEvent::Resumed => {
+                    engine.handle_event(Event::Resumed);
+                }
+                WinitEvent::RedrawRequested(_) => {
                     redraw = true;
                 }
-                Event::MainEventsCleared => {
-                    engine.window.request_redraw();
+                WinitEvent::MainEventsCleared
+                    if engine.is_paused() && last_frame_paused && !force_exit =>
+                {
+                    *control_flow = winit::event_loop::ControlFlow::Wait;
+                    paused = true;
+                }
+                WinitEvent::MainEventsCleared => {
+                    if paused {
+                        // The time spent paused isn't part of the next frame
+                        engine.timer = instant::Instant::now();
+                        paused = false;
+                    }
+                    let max_fps = match engine.unfocused_mode {
+                        UnfocusedMode::Throttle(fps) if engine.is_in_background() => {
+                            Some(engine.max_fps.map_or(fps, |max_fps| max_fps.min(fps)))
+                        }
+                        _ => engine.max_fps,
+                    };
+                    let next_frame = max_fps.map(|fps| {
+                        engine.timer
+                            + std::time::Duration::from_secs_f64(1.0 / f64::from(fps.max(1)))
+                    });
+                    match next_frame {
+                        Some(next_frame) if instant::Instant::now() < next_frame => {
+                            *control_flow = winit::event_loop::ControlFlow::WaitUntil(next_frame);
+                        }
+                        _ => {
+                            *control_flow = winit::event_loop::ControlFlow::Poll;
+                            engine.window().request_redraw();
+                        }
+                    }
                 }
                 _ => {}
             }
-            if redraw {
+            if redraw && (force_exit || !engine.is_paused() || !last_frame_paused) {
                 engine.elapsed = (instant::Instant::now()
                     .checked_duration_since(engine.timer)
                     .expect("Error with timer"))
@@ -198,34 +265,118 @@ impl EngineWrapper {
                 if engine.frame_timer > 1.0 {
                     engine.frame_timer -= 1.0;
                     engine
-                        .window
+                        .window()
                         .set_title(&format!("{} - {}fps", engine.title, engine.frame_count));
                     engine.frame_count = 0;
                 }
-                let r = (main_func)(&mut engine);
-                if r.is_err() || r.as_ref().ok() == Some(&false) || force_exit {
-                    if let Err(e) = r {
-                        if cfg!(debug_assertions) {
-                            println!("Game Stopped:\n{:?}", e);
-                        } else {
-                            println!("Game Stopped:\n{}", e);
-                        }
-                    }
+                engine.begin_frame();
+                let r = engine.update_app(&mut game);
+                if !report_stop(r) || force_exit {
+                    running = false;
+                    game.on_user_destroy(&mut engine);
+                    destroyed = true;
                     *control_flow = winit::event_loop::ControlFlow::Exit;
                 }
-                let (raw, readlock) = engine.screen.get_ref().get_read_lock();
-                engine.handler.render(raw);
+                engine.present();
+                last_frame_paused = engine.is_paused();
                 redraw = false;
                 redraw_last_frame = true;
-                drop(readlock);
             }
         });
     }
+
+    /// Same as [`EngineWrapper::run`], but `update` is called at the fixed rate of the
+    /// [`FixedTimestep`] with the duration of a tick, and `draw` once per frame with the
+    /// interpolation alpha, see the [`timestep`](super::timestep) module.
+    ///
+    /// The game stops when either function returns `Ok(false)` or an error
+    pub fn run_fixed<U, D>(self, mut timestep: FixedTimestep, mut update: U, mut draw: D) -> !
+    where
+        U: (FnMut(&mut Engine, f64) -> Result<bool, Box<dyn std::error::Error>>) + 'static,
+        D: (FnMut(&mut Engine, f64) -> Result<bool, Box<dyn std::error::Error>>) + 'static,
+    {
+        self.run(move |engine| timestep.frame(engine, &mut update, &mut draw))
+    }
+
+    /// Same as [`EngineWrapper::run`] but for an engine created with
+    /// [`EngineWrapper::new_headless`]
+    ///
+    /// It will run at most `frames` frames, stopping sooner if the function returns `Ok(false)`.
+    /// Every frame will have an `elapsed` of exactly [`HEADLESS_ELAPSED`] seconds so runs are
+    /// reproducible.
+    ///
+    /// Returns the last presented frame
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the main function if it returned one.
+    pub fn run_headless<F>(
+        self,
+        frames: usize,
+        main_func: F,
+    ) -> Result<Sprite, Box<dyn std::error::Error>>
+    where
+        F: FnMut(&mut Engine) -> Result<bool, Box<dyn std::error::Error>>,
+    {
+        self.run_app_headless(frames, FnGame(main_func))
+    }
+
+    /// Same as [`EngineWrapper::run_app`] but for an engine created with
+    /// [`EngineWrapper::new_headless`], see [`EngineWrapper::run_headless`].
+    /// [`Game::on_user_destroy`] is called before returning, even if there was an error
+    ///
+    /// Returns the last presented frame
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the game if it returned one.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn run_app_headless<G: Game>(
+        mut self,
+        frames: usize,
+        mut game: G,
+    ) -> Result<Sprite, Box<dyn std::error::Error>> {
+        let mut engine = self.0.take().unwrap();
+        let result = match game.on_user_create(&mut engine) {
+            Ok(true) => engine.run_headless_frames(frames, &mut game),
+            Ok(false) => Ok(()),
+            Err(e) => Err(e),
+        };
+        game.on_user_destroy(&mut engine);
+        result?;
+        let Handler::Headless(ctx) = &engine.handler else {
+            panic!("run_headless can only be used with an headless engine");
+        };
+        let (raw, (width, height)) = ctx.frame();
+        Ok(Sprite::load_rgba(raw, width as usize, height as usize)?)
+    }
+}
+
+/// Print the error (if any), return true if the game should keep running
+fn report_stop(r: Result<bool, Box<dyn std::error::Error>>) -> bool {
+    match r {
+        Ok(running) => running,
+        Err(e) => {
+            if cfg!(debug_assertions) {
+                println!("Game Stopped:\n{e:?}");
+            } else {
+                println!("Game Stopped:\n{e}");
+            }
+            false
+        }
+    }
 }
 
+/// The `elapsed` time (in seconds) of every frame when running headless
+pub const HEADLESS_ELAPSED: f64 = 1.0 / 60.0;
+
+/// How many pixels of a precise scroll (touchpads) make a line of a mouse wheel
+const PIXELS_PER_LINE: f64 = 20.0;
+
 /**
  *  Bone of the Engine, join everything;
  **/
+#[allow(clippy::struct_excessive_bools)]
 pub struct Engine {
     /* FRONTEND */
     /// Main title of the window, Window's full title will be "Title - fps"
@@ -242,14 +393,45 @@ pub struct Engine {
 
     /* BACKEND */
     pub(crate) screen: DrawingSprite<Sprite>,
-    pub(crate) handler: px_backend::Context,
+    pub(crate) layers: Vec<LayerData>,
+    pub(crate) draw_target: Layer,
+    pub(crate) handler: Handler,
     pub(crate) textsheet_decal: Decal,
     k_pressed: std::collections::HashSet<inputs::Key>,
     k_held: std::collections::HashSet<inputs::Key>,
     k_released: std::collections::HashSet<inputs::Key>,
+    k_down: std::collections::HashSet<inputs::Key>,
+    k_repeated: std::collections::HashSet<inputs::Key>,
+    modifiers: inputs::Modifiers,
+    actions: ActionMap,
+    gamepads: std::collections::BTreeMap<GamepadId, GamepadState>,
+    gamepad_backends: Vec<Box<dyn GamepadBackend>>,
+    gamepad_dead_zone: f32,
     mouse: Mouse,
     event_loop: Option<winit::event_loop::EventLoop<()>>,
-    window: winit::window::Window,
+    window: Option<winit::window::Window>,
+    screenshot_key: Option<(inputs::Keycodes, std::path::PathBuf)>,
+    screenshot_count: u32,
+    recorder: Option<Recorder>,
+    recording_key: Option<(inputs::Keycodes, RecordingFormat, u32, std::path::PathBuf)>,
+    recording_count: u32,
+    input_recording: Option<(InputTrace, Vec<InputEvent>)>,
+    replay: Option<std::vec::IntoIter<FrameInputs>>,
+    frame_events: Vec<Event>,
+    text_input: String,
+    hovered_files: Vec<std::path::PathBuf>,
+    dropped_files: Vec<std::path::PathBuf>,
+    max_fps: Option<u32>,
+    unfocused_mode: UnfocusedMode,
+    scaling: Scaling,
+    resizable: bool,
+    window_mode: WindowMode,
+    present_mode: PresentMode,
+    position: Option<(i32, i32)>,
+    icon: Option<Sprite>,
+    focused: bool,
+    occluded: bool,
+    suspended: bool,
 }
 impl std::fmt::Debug for Engine {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -264,16 +446,20 @@ impl std::fmt::Debug for Engine {
 
 impl Drop for Engine {
     fn drop(&mut self) {
-        let decal = self.textsheet_decal.clone_decal();
-        self.destroy_decal(&decal);
+        self.textsheet_decal.destroy(&mut self.handler);
+        if let Err(e) = self.stop_recording() {
+            eprintln!("Could not finish the recording: {e}");
+        }
     }
 }
 
 impl Engine {
     /// Create a new [`Engine`]
-    async fn new(title: String, size: (u32, u32, u32)) -> Self {
+    #[allow(clippy::too_many_lines)]
+    async fn new(builder: EngineBuilder) -> Self {
+        let size = (builder.size.0, builder.size.1, builder.pixel_size);
         let event_loop = winit::event_loop::EventLoop::new();
-        let window = winit::window::WindowBuilder::new()
+        let mut window_builder = winit::window::WindowBuilder::new()
             .with_inner_size(
                 #[allow(clippy::cast_precision_loss)]
                 {
@@ -283,8 +469,37 @@ impl Engine {
                     )
                 },
             )
-            .with_title(&title)
-            .with_resizable(false)
+            .with_title(&builder.title)
+            .with_resizable(builder.resizable)
+            .with_decorations(builder.window_mode == WindowMode::Windowed)
+            .with_fullscreen(match builder.window_mode {
+                WindowMode::Windowed | WindowMode::Borderless => None,
+                WindowMode::BorderlessFullscreen => {
+                    Some(winit::window::Fullscreen::Borderless(None))
+                }
+                WindowMode::Fullscreen => event_loop
+                    .primary_monitor()
+                    .and_then(|monitor| {
+                        monitor.video_modes().max_by_key(|mode| {
+                            let size = mode.size();
+                            (size.width * size.height, mode.refresh_rate_millihertz())
+                        })
+                    })
+                    .map(winit::window::Fullscreen::Exclusive),
+            });
+        if let Some((x, y)) = builder.position {
+            window_builder = window_builder.with_position(winit::dpi::PhysicalPosition::new(x, y));
+        }
+        if let Some(icon) = &builder.icon {
+            let (raw, lock) = icon.get_read_lock();
+            let icon = winit::window::Icon::from_rgba(raw.to_vec(), icon.width(), icon.height());
+            drop(lock);
+            match icon {
+                Ok(icon) => window_builder = window_builder.with_window_icon(Some(icon)),
+                Err(e) => eprintln!("Invalid window icon: {e}"),
+            }
+        }
+        let window = window_builder
             .build(&event_loop)
             .expect("Error when constructing window");
         window.set_visible(false);
@@ -317,14 +532,37 @@ impl Engine {
             }
         }
 
-        let mut handler = px_backend::Context::new(&window, size).await;
+        let options = px_backend::ContextOptions {
+            present_mode: builder.present_mode,
+            scaling: builder.scaling,
+            capture: builder.capture,
+        };
+        let handler = Handler::Window(px_backend::Context::new(&window, size, options).await);
+        window.set_visible(true);
+        Self::with_handler(builder, handler, Some(window), Some(event_loop))
+    }
+
+    /// Make a Engine without window, the frames are kept in memory
+    fn new_headless(builder: EngineBuilder) -> Self {
+        let size = (builder.size.0, builder.size.1, builder.pixel_size);
+        let handler = Handler::Headless(px_backend::headless::Context::new(size));
+        Self::with_handler(builder, handler, None, None)
+    }
+
+    /// The fields shared by the engines with and without a window
+    fn with_handler(
+        builder: EngineBuilder,
+        mut handler: Handler,
+        window: Option<winit::window::Window>,
+        event_loop: Option<winit::event_loop::EventLoop<()>>,
+    ) -> Self {
+        let size = (builder.size.0, builder.size.1, builder.pixel_size);
         let screen = DrawingSprite::new(Sprite::new(size.0, size.1));
-        let textsheet_decal =
-            crate::decals::Decal::new(&mut handler, SmartDrawingTrait::get_textsheet(&screen));
+        let textsheet_decal = Decal::new(&mut handler, SmartDrawingTrait::get_textsheet(&screen));
         Engine {
             /* FRONTEND */
             size,
-            title,
+            title: builder.title,
 
             /* TIME */
             timer: instant::Instant::now(),
@@ -334,24 +572,498 @@ impl Engine {
             /* BACKEND */
             handler,
             screen,
+            layers: Vec::new(),
+            draw_target: Layer::SCREEN,
             textsheet_decal,
             k_pressed: std::collections::HashSet::new(),
             k_held: std::collections::HashSet::new(),
             k_released: std::collections::HashSet::new(),
-            mouse: Mouse::new(),
-            window: {
-                window.set_visible(true);
-                window
-            },
-            event_loop: Some(event_loop),
+            k_down: std::collections::HashSet::new(),
+            k_repeated: std::collections::HashSet::new(),
+            modifiers: inputs::Modifiers::empty(),
+            actions: ActionMap::new(),
+            gamepads: std::collections::BTreeMap::new(),
+            gamepad_backends: Vec::new(),
+            gamepad_dead_zone: 0.15,
+            mouse: Mouse::default(),
+            screenshot_key: None,
+            screenshot_count: 0,
+            recorder: None,
+            recording_key: None,
+            recording_count: 0,
+            input_recording: None,
+            replay: None,
+            frame_events: Vec::new(),
+            text_input: String::new(),
+            hovered_files: Vec::new(),
+            dropped_files: Vec::new(),
+            max_fps: builder.max_fps,
+            unfocused_mode: builder.unfocused_mode,
+            scaling: builder.scaling,
+            resizable: builder.resizable,
+            window_mode: builder.window_mode,
+            present_mode: builder.present_mode,
+            position: builder.position,
+            icon: builder.icon,
+            focused: true,
+            occluded: false,
+            suspended: false,
+            window,
+            event_loop,
         }
     }
-    #[cfg(not(target_arch = "wasm32"))]
 
-    /// Make a Engine but sync
-    fn new_sync(title: String, size: (u32, u32, u32)) -> Self {
-        futures::executor::block_on(Self::new(title, size))
+    fn window(&self) -> &winit::window::Window {
+        self.window
+            .as_ref()
+            .expect("An headless engine doesn't have a window")
+    }
+
+    fn run_headless_frames<G: Game>(
+        &mut self,
+        frames: usize,
+        game: &mut G,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for _ in 0..frames {
+            self.elapsed = HEADLESS_ELAPSED;
+            self.begin_frame();
+            let r = self.update_app(game)?;
+            self.present();
+            self.roll_inputs();
+            if !r {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Give the inputs of the frame to the game, then update it
+    fn update_app<G: Game>(&mut self, game: &mut G) -> Result<bool, Box<dyn std::error::Error>> {
+        for event in self.frame_events.clone() {
+            game.on_event(self, &event);
+        }
+        game.on_user_update(self, self.elapsed)
+    }
+
+    /// Replace the inputs and `elapsed` of the frame by the ones of the trace, if one is replayed
+    fn begin_frame(&mut self) {
+        let mut events = Vec::new();
+        for backend in &mut self.gamepad_backends {
+            events.extend(backend.poll());
+        }
+        for event in events {
+            self.handle_input(InputEvent::Gamepad(event));
+        }
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+        match replay.next() {
+            Some(frame) => {
+                self.elapsed = frame.elapsed;
+                for input in frame.inputs {
+                    self.record_input(input);
+                    self.apply_input(input);
+                }
+            }
+            None => self.replay = None,
+        }
+    }
+
+    /// Send the screen to the handler
+    fn present(&mut self) {
+        if let Some((trace, inputs)) = &mut self.input_recording {
+            trace.frames.push(FrameInputs {
+                elapsed: self.elapsed,
+                inputs: std::mem::take(inputs),
+            });
+        }
+        if let Some((key, directory)) = &self.screenshot_key {
+            if self.get_key(*key).pressed {
+                let directory = directory.clone();
+                self.save_numbered_screenshot(&directory);
+            }
+        }
+        if let Some((key, format, fps, directory)) = &self.recording_key {
+            if self.get_key(*key).pressed {
+                let (format, fps, directory) = (*format, *fps, directory.clone());
+                self.toggle_numbered_recording(format, fps, &directory);
+            }
+        }
+        if let Some(mut recorder) = self.recorder.take() {
+            let elapsed = self.elapsed;
+            match recorder.capture(elapsed, || self.screenshot()) {
+                Ok(()) => self.recorder = Some(recorder),
+                Err(e) => eprintln!("Recording stopped: {e}"),
+            }
+        }
+        let handler = &mut self.handler;
+        layer::with_frame(&self.screen, &self.layers, |raw, layers| {
+            handler.render(raw, layers);
+        });
+    }
+
+    /// Feed an input to the engine, it is recorded if an input trace is being recorded and ignored
+    /// while one is replayed
+    pub(crate) fn handle_input(&mut self, input: InputEvent) {
+        if self.replay.is_some() {
+            return;
+        }
+        self.record_input(input);
+        self.apply_input(input);
+    }
+
+    /// Feed an event to the engine, inputs go through [`Engine::handle_input`]
+    pub(crate) fn handle_event(&mut self, event: Event) {
+        let lost_focus = event == Event::Focused(false);
+        match &event {
+            Event::Input(input) => {
+                self.handle_input(*input);
+                return;
+            }
+            Event::Resized(..) => {}
+            Event::Focused(focused) => self.focused = *focused,
+            Event::Occluded(occluded) => self.occluded = *occluded,
+            Event::Suspended => self.suspended = true,
+            Event::Resumed => self.suspended = false,
+            Event::HoveredFile(path) => self.hovered_files.push(path.clone()),
+            Event::HoveredFileCancelled => self.hovered_files.clear(),
+            Event::DroppedFile(path) => {
+                self.hovered_files.retain(|hovered| hovered != path);
+                self.dropped_files.push(path.clone());
+            }
+        }
+        self.frame_events.push(event);
+        if lost_focus {
+            self.release_held_inputs();
+        }
+    }
+
+    /// Release the keys and mouse buttons that are down: once the window is unfocused, it isn't
+    /// told when they are released
+    fn release_held_inputs(&mut self) {
+        let mut keys: Vec<inputs::Keycodes> = self.k_down.iter().map(|k| k.key).collect();
+        keys.sort_unstable();
+        for key in keys {
+            self.handle_input(InputEvent::KeyRelease(key));
+        }
+        let mut buttons: Vec<MouseBtn> = self.mouse.down.iter().copied().collect();
+        buttons.sort_unstable();
+        for btn in buttons {
+            self.handle_input(InputEvent::MouseRelease(btn));
+        }
+        if !self.modifiers.is_empty() {
+            self.handle_input(InputEvent::Modifiers(inputs::Modifiers::empty()));
+        }
+    }
+
+    fn record_input(&mut self, input: InputEvent) {
+        if let Some((_, inputs)) = &mut self.input_recording {
+            inputs.push(input);
+        }
+    }
+
+    fn apply_input(&mut self, input: InputEvent) {
+        let input = match input {
+            InputEvent::KeyPress(key) if self.k_down.has(key) => InputEvent::KeyRepeat(key),
+            input => input,
+        };
+        self.frame_events.push(Event::Input(input));
+        match input {
+            InputEvent::KeyPress(key) => self.key_down(key),
+            InputEvent::KeyRelease(key) => self.key_up(key),
+            InputEvent::KeyRepeat(key) => {
+                self.k_repeated.insert(inputs::Key { key });
+            }
+            InputEvent::Modifiers(modifiers) => self.modifiers = modifiers,
+            InputEvent::MouseMove(x, y) => self.set_mouse_location((x, y)),
+            InputEvent::MouseEntered => self.mouse.in_window = true,
+            InputEvent::MouseLeft => {
+                self.mouse.in_window = false;
+                self.mouse.tracked = false;
+            }
+            InputEvent::MouseMotion(x, y) => {
+                self.mouse.raw_delta.0 += x;
+                self.mouse.raw_delta.1 += y;
+            }
+            InputEvent::MousePress(btn) => self.mouse_btn_down(btn),
+            InputEvent::MouseRelease(btn) => self.mouse_btn_up(btn),
+            InputEvent::MouseWheel(wheel) => {
+                let (x, y) = wheel.to_scroll();
+                self.scroll_mouse(x, y);
+            }
+            InputEvent::MouseScroll(x, y) => self.scroll_mouse(x, y),
+            InputEvent::Character(c) => self.text_input.push(c),
+            InputEvent::Gamepad(event) => self.gamepad_event(event),
+        }
+    }
+
+    fn gamepad_event(&mut self, event: GamepadEvent) {
+        match event {
+            GamepadEvent::Connected(id) => {
+                self.gamepads.entry(id).or_default();
+            }
+            GamepadEvent::Disconnected(id) => {
+                self.gamepads.remove(&id);
+            }
+            GamepadEvent::ButtonPressed(id, button) => {
+                self.gamepads.entry(id).or_default().press(button);
+            }
+            GamepadEvent::ButtonReleased(id, button) => {
+                self.gamepads.entry(id).or_default().release(button);
+            }
+            GamepadEvent::AxisChanged(id, axis, value) => {
+                self.gamepads.entry(id).or_default().axes[axis.index()] = value.clamp(-1.0, 1.0);
+            }
+        }
+    }
+
+    /// Register a key as being pressed down
+    fn key_down(&mut self, key: inputs::Keycodes) {
+        self.k_down.insert(inputs::Key { key });
+        self.k_pressed.insert(inputs::Key { key });
+    }
+
+    /// Register a key as being released, it stays pressed if it was pressed during the same frame
+    fn key_up(&mut self, key: inputs::Keycodes) {
+        self.k_down.remove(&inputs::Key { key });
+        self.k_held.remove(&inputs::Key { key });
+        self.k_released.insert(inputs::Key { key });
+    }
+
+    /// Register a mouse button as being pressed down
+    fn mouse_btn_down(&mut self, btn: MouseBtn) {
+        if self.mouse.down.insert(btn) {
+            self.mouse
+                .buttons
+                .entry(btn)
+                .or_insert(Input::default())
+                .pressed = true;
+        }
+    }
+
+    /// Register a mouse button as being released, it stays pressed if it was pressed during the
+    /// same frame
+    fn mouse_btn_up(&mut self, btn: MouseBtn) {
+        self.mouse.down.remove(&btn);
+        let input = self.mouse.buttons.entry(btn).or_insert(Input::default());
+        input.released = true;
+        input.held = false;
+    }
+
+    fn set_mouse_location(&mut self, pos: (f32, f32)) {
+        // The first position after entering the window isn't a movement
+        if self.mouse.tracked {
+            self.mouse.delta.0 += pos.0 - self.mouse.pos.0;
+            self.mouse.delta.1 += pos.1 - self.mouse.pos.1;
+        }
+        self.mouse.pos = pos;
+        self.mouse.in_window = true;
+        self.mouse.tracked = true;
+    }
+
+    fn scroll_mouse(&mut self, x: f32, y: f32) {
+        self.mouse.scroll.0 += x;
+        self.mouse.scroll.1 += y;
+        self.mouse.wheel = MouseWheel::from_scroll(self.mouse.scroll.0, self.mouse.scroll.1);
+    }
+
+    /// Go from one frame to the next one: pressed inputs become held, released are forgotten
+    fn roll_inputs(&mut self) {
+        self.k_held.clone_from(&self.k_down);
+        self.k_pressed.clear();
+        self.k_released.clear();
+        self.k_repeated.clear();
+        self.frame_events.clear();
+        self.text_input.clear();
+        self.dropped_files.clear();
+        for (btn, input) in &mut self.mouse.buttons {
+            input.pressed = false;
+            input.held = self.mouse.down.contains(btn);
+            input.released = false;
+        }
+        self.mouse.wheel = MouseWheel::None;
+        self.mouse.scroll = (0.0, 0.0);
+        self.mouse.delta = (0.0, 0.0);
+        self.mouse.raw_delta = (0.0, 0.0);
+        for gamepad in self.gamepads.values_mut() {
+            gamepad.roll();
+        }
+    }
+    /// Does the window have the keyboard focus
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Is the window minimised or fully hidden
+    pub fn is_occluded(&self) -> bool {
+        self.occluded
+    }
+
+    /// Is the window unfocused, minimised or suspended by the system, the [`UnfocusedMode`] is
+    /// then used
+    pub fn is_in_background(&self) -> bool {
+        !self.focused || self.occluded || self.suspended
+    }
+
+    /// Set what the engine does while the window is in the background, see
+    /// [`EngineBuilder::unfocused_mode`]. An headless engine always keeps running
+    pub fn set_unfocused_mode(&mut self, unfocused_mode: UnfocusedMode) {
+        self.unfocused_mode = unfocused_mode;
+    }
+
+    /// What the engine does while the window is in the background
+    pub fn get_unfocused_mode(&self) -> UnfocusedMode {
+        self.unfocused_mode
     }
+
+    /// The maximum number of frames per second, `None` if it isn't capped, see
+    /// [`EngineBuilder::max_fps`]
+    pub fn get_max_fps(&self) -> Option<u32> {
+        self.max_fps
+    }
+
+    fn is_paused(&self) -> bool {
+        self.unfocused_mode == UnfocusedMode::Pause && self.is_in_background()
+    }
+
+    /// Set how the screen is scaled when the window is bigger than it, the remaining space is
+    /// filled with black bars
+    pub fn set_scaling(&mut self, scaling: Scaling) {
+        self.scaling = scaling;
+        self.handler.set_scaling(scaling);
+    }
+
+    /// How the screen is scaled when the window is bigger than it
+    pub fn get_scaling(&self) -> Scaling {
+        self.scaling
+    }
+
+    /// The size of a pixel on the window, see [`EngineBuilder::pixel_size`]
+    pub fn get_pixel_size(&self) -> u32 {
+        self.size.2
+    }
+
+    /// Can the window be resized by the user, see [`EngineBuilder::resizable`]
+    pub fn is_resizable(&self) -> bool {
+        self.resizable
+    }
+
+    /// How the window was shown when it was created, see [`EngineBuilder::window_mode`]
+    pub fn get_window_mode(&self) -> WindowMode {
+        self.window_mode
+    }
+
+    /// How the frames were asked to be synced with the screen, see
+    /// [`EngineBuilder::present_mode`]
+    pub fn get_present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    /// The position the window was created at, `None` if the system chose it, see
+    /// [`EngineBuilder::position`]
+    pub fn get_window_position(&self) -> Option<(i32, i32)> {
+        self.position
+    }
+
+    /// The icon of the window, see [`EngineBuilder::icon`]
+    pub fn get_icon(&self) -> Option<&Sprite> {
+        self.icon.as_ref()
+    }
+
+    /// Create a new layer, drawn over the screen and the layers created before it.
+    /// It starts transparent, enabled, not moved nor stretched and not tinted
+    pub fn create_layer(&mut self) -> Layer {
+        self.layers.push(LayerData::new((self.size.0, self.size.1)));
+        Layer(self.layers.len())
+    }
+
+    /// Select the layer the drawing functions and the decals go to, the decals are drawn right
+    /// over the layer they were drawn on. Use [`Layer::SCREEN`] to draw on the screen again.
+    ///
+    /// The pixel mode, blend factor, clipping rectangles and transforms follow the target, they
+    /// are the same before and after the change
+    ///
+    /// # Panics
+    ///
+    /// If the layer wasn't created by this engine
+    pub fn set_draw_target(&mut self, layer: Layer) {
+        assert!(layer.0 <= self.layers.len(), "Unknown layer {:?}", layer);
+        // The state goes through the screen to the new target, the one left on the previous
+        // target doesn't matter as it isn't drawn on anymore
+        for Layer(n) in [self.draw_target, layer] {
+            if n != 0 {
+                self.screen.swap_draw_state(&mut self.layers[n - 1].sprite);
+            }
+        }
+        self.draw_target = layer;
+    }
+
+    /// The layer the drawing functions and the decals go to
+    pub fn get_draw_target(&self) -> Layer {
+        self.draw_target
+    }
+
+    /// Show or hide a layer, with the decals drawn on it
+    ///
+    /// # Panics
+    ///
+    /// If the layer is [`Layer::SCREEN`] or wasn't created by this engine
+    pub fn set_layer_enabled(&mut self, layer: Layer, enabled: bool) {
+        self.layer_mut(layer).enabled = enabled;
+    }
+
+    /// Move the layer, the offset is where its top left corner is drawn on the screen, in pixels
+    ///
+    /// # Panics
+    ///
+    /// If the layer is [`Layer::SCREEN`] or wasn't created by this engine
+    pub fn set_layer_offset<P: Into<Vf2d>>(&mut self, layer: Layer, offset: P) {
+        self.layer_mut(layer).offset = offset.into();
+    }
+
+    /// Stretch the layer horizontally and vertically, from its top left corner
+    ///
+    /// # Panics
+    ///
+    /// If the layer is [`Layer::SCREEN`] or wasn't created by this engine
+    pub fn set_layer_scale<P: Into<Vf2d>>(&mut self, layer: Layer, scale: P) {
+        self.layer_mut(layer).scale = scale.into();
+    }
+
+    /// Multiply the colour of the layer's pixels by `tint`, its alpha makes the layer translucent
+    ///
+    /// # Panics
+    ///
+    /// If the layer is [`Layer::SCREEN`] or wasn't created by this engine
+    pub fn set_layer_tint(&mut self, layer: Layer, tint: Color) {
+        self.layer_mut(layer).tint = tint;
+    }
+
+    fn layer_mut(&mut self, layer: Layer) -> &mut LayerData {
+        assert!(
+            layer != Layer::SCREEN,
+            "The screen can't be changed like a layer"
+        );
+        self.layers
+            .get_mut(layer.0 - 1)
+            .unwrap_or_else(|| panic!("Unknown layer {:?}", layer))
+    }
+
+    /// The sprite the drawing functions go to
+    pub(crate) fn target(&self) -> &DrawingSprite<Sprite> {
+        match self.draw_target {
+            Layer::SCREEN => &self.screen,
+            Layer(n) => &self.layers[n - 1].sprite,
+        }
+    }
+
+    pub(crate) fn target_mut(&mut self) -> &mut DrawingSprite<Sprite> {
+        match self.draw_target {
+            Layer::SCREEN => &mut self.screen,
+            Layer(n) => &mut self.layers[n - 1].sprite,
+        }
+    }
+
     /// Return the current Target size in pixel
     pub fn size(&self) -> Vu2d {
         self.screen.get_size()
@@ -365,30 +1077,455 @@ impl Engine {
             self.k_held.has(keycode),
             self.k_released.has(keycode),
         )
+        .with_repeated(self.k_repeated.has(keycode))
+    }
+
+    /// Get the state of the modifier keys, as reported by the system or from the modifier keys
+    /// that are down (so inputs fed by [`testing`](super::testing) are taken into account)
+    pub fn get_modifiers(&self) -> inputs::Modifiers {
+        use inputs::{Keycodes, Modifiers};
+        let mut modifiers = self.modifiers;
+        for (keys, modifier) in [
+            ([Keycodes::LShift, Keycodes::RShift], Modifiers::SHIFT),
+            ([Keycodes::LControl, Keycodes::RControl], Modifiers::CTRL),
+            ([Keycodes::LAlt, Keycodes::RAlt], Modifiers::ALT),
+            ([Keycodes::LWin, Keycodes::RWin], Modifiers::LOGO),
+        ] {
+            if keys.iter().any(|key| self.k_down.has(*key)) {
+                modifiers |= modifier;
+            }
+        }
+        modifiers
+    }
+
+    /// Get the status of a key combination: the key, with exactly the given modifiers down.
+    /// `Ctrl+S` is `get_chord(Modifiers::CTRL, Keycodes::S)`, which isn't pressed by `Ctrl+Shift+S`
+    pub fn get_chord(&self, modifiers: inputs::Modifiers, keycode: inputs::Keycodes) -> Input {
+        if self.get_modifiers() == modifiers {
+            self.get_key(keycode)
+        } else {
+            Input::default()
+        }
+    }
+
+    /// Poll `backend` at the start of every frame for gamepad events, see
+    /// [`gamepad`](super::gamepad)
+    pub fn add_gamepad_backend<B: GamepadBackend + 'static>(&mut self, backend: B) {
+        self.gamepad_backends.push(Box::new(backend));
+    }
+
+    /// Get the ids of the connected gamepads
+    pub fn get_gamepads(&self) -> Vec<GamepadId> {
+        self.gamepads.keys().copied().collect()
+    }
+
+    /// Get the status of a gamepad button, never pressed if the gamepad isn't connected
+    pub fn get_gamepad_button(&self, id: GamepadId, button: GamepadButton) -> Input {
+        self.gamepads
+            .get(&id)
+            .and_then(|gamepad| gamepad.buttons.get(&button))
+            .copied()
+            .unwrap_or(Input::default())
+    }
+
+    /// Get the value of a gamepad axis with the dead zone removed, 0 if the gamepad isn't
+    /// connected
+    pub fn get_gamepad_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        self.gamepads.get(&id).map_or(0.0, |gamepad| {
+            apply_dead_zone(gamepad.axes[axis.index()], self.gamepad_dead_zone)
+        })
+    }
+
+    /// Values of the gamepad axes closer to 0 than `dead_zone` are read as 0, so sticks that don't
+    /// rest exactly at the center don't drift. The default is 0.15
+    pub fn set_gamepad_dead_zone(&mut self, dead_zone: f32) {
+        self.gamepad_dead_zone = dead_zone.clamp(0.0, 0.99);
+    }
+
+    /// Use the given actions for [`Engine::get_action`], [`Engine::get_axis`] and
+    /// [`Engine::get_axis_2d`]
+    pub fn set_actions(&mut self, actions: ActionMap) {
+        self.actions = actions;
+    }
+
+    /// Get the current actions
+    pub fn actions(&self) -> &ActionMap {
+        &self.actions
+    }
+
+    /// Get the current actions to change their bindings
+    pub fn actions_mut(&mut self) -> &mut ActionMap {
+        &mut self.actions
+    }
+
+    /// Get the status of a single binding
+    pub fn get_binding(&self, binding: Binding) -> Input {
+        match binding {
+            Binding::Key(key) => self.get_key(key),
+            Binding::Mouse(btn) => self.get_mouse_btn(btn),
+            Binding::Wheel(wheel) => Input::new(self.mouse.wheel == wheel, false, false),
+            Binding::GamepadButton(button) => {
+                let inputs: Vec<Input> = self
+                    .gamepads
+                    .keys()
+                    .map(|id| self.get_gamepad_button(*id, button))
+                    .collect();
+                combine_inputs(&inputs)
+            }
+            Binding::GamepadAxis { axis, positive } => {
+                let half = |value: f32| if positive { value } else { -value } >= 0.5;
+                let now = self
+                    .gamepads
+                    .values()
+                    .any(|gamepad| half(gamepad.axes[axis.index()]));
+                let before = self
+                    .gamepads
+                    .values()
+                    .any(|gamepad| half(gamepad.previous_axes[axis.index()]));
+                Input::new(now && !before, now && before, !now && before)
+            }
+        }
+    }
+
+    /// How much a binding is pushed, between 0 and 1
+    fn binding_value(&self, binding: Binding) -> f32 {
+        match binding {
+            Binding::GamepadAxis { axis, positive } => self
+                .gamepads
+                .keys()
+                .map(|id| {
+                    let value = self.get_gamepad_axis(*id, axis);
+                    if positive {
+                        value
+                    } else {
+                        -value
+                    }
+                })
+                .fold(0.0, f32::max),
+            binding => {
+                let input = self.get_binding(binding);
+                f32::from(u8::from(input.pressed || input.held))
+            }
+        }
+    }
+
+    /// Get the status of a button action, combining all its bindings: it is pressed when the
+    /// first of them is pressed and released when the last of them is released.
+    /// Unknown actions are never pressed
+    pub fn get_action(&self, name: &str) -> Input {
+        let inputs: Vec<Input> = self
+            .actions
+            .buttons
+            .get(name)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|binding| self.get_binding(*binding))
+            .collect();
+        combine_inputs(&inputs)
+    }
+
+    fn axis_value(&self, axis: &Axis) -> f32 {
+        let strength = |bindings: &[Binding]| {
+            bindings
+                .iter()
+                .map(|binding| self.binding_value(*binding))
+                .fold(0.0, f32::max)
+        };
+        (strength(&axis.positive) - strength(&axis.negative)).clamp(-1.0, 1.0)
+    }
+
+    /// Get the value of an axis action, between -1 and 1. Unknown axes are 0
+    pub fn get_axis(&self, name: &str) -> f32 {
+        self.actions
+            .axes
+            .get(name)
+            .map_or(0.0, |axis| self.axis_value(axis))
+    }
+
+    /// Get the value of a 2D axis action, with a length of at most 1 (so moving diagonally
+    /// isn't faster). Unknown axes are `(0.0, 0.0)`
+    pub fn get_axis_2d(&self, name: &str) -> (f32, f32) {
+        self.actions.axes_2d.get(name).map_or((0.0, 0.0), |axis| {
+            let (x, y) = (self.axis_value(&axis.x), self.axis_value(&axis.y));
+            let length = x.hypot(y);
+            if length > 1.0 {
+                (x / length, y / length)
+            } else {
+                (x, y)
+            }
+        })
+    }
+
+    /// Get the events (inputs, window changes) since the previous frame, in order.
+    /// See the [`events`](super::events) module
+    pub fn events(&self) -> &[Event] {
+        &self.frame_events
+    }
+
+    /// Get the first key, mouse button or wheel direction pressed during the last frame, to let
+    /// the player choose a binding
+    pub fn get_pressed_binding(&self) -> Option<Binding> {
+        self.frame_events.iter().find_map(|event| match *event {
+            Event::Input(InputEvent::KeyPress(key)) => Some(Binding::Key(key)),
+            Event::Input(InputEvent::MousePress(btn)) => Some(Binding::Mouse(btn)),
+            Event::Input(InputEvent::MouseWheel(wheel)) if wheel != MouseWheel::None => {
+                Some(Binding::Wheel(wheel))
+            }
+            Event::Input(InputEvent::MouseScroll(x, y))
+                if MouseWheel::from_scroll(x, y) != MouseWheel::None =>
+            {
+                Some(Binding::Wheel(MouseWheel::from_scroll(x, y)))
+            }
+            Event::Input(InputEvent::Gamepad(GamepadEvent::ButtonPressed(_, button))) => {
+                Some(Binding::GamepadButton(button))
+            }
+            _ => None,
+        })
     }
     /// Get the status of a Mouse Button
     pub fn get_mouse_btn(&self, btn: MouseBtn) -> Input {
-        self.mouse.buttons[match btn {
-            MouseBtn::Left => 0,
-            MouseBtn::Right => 1,
-            MouseBtn::Middle => 2,
-        }]
+        self.mouse
+            .buttons
+            .get(&btn)
+            .copied()
+            .unwrap_or(Input::default())
     }
 
     /// Get the mouse location (in pixel) on the screen
     /// Will be defaulted to (0,0) at the start of the program
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     pub fn get_mouse_location(&self) -> (u32, u32) {
+        (
+            (self.mouse.pos.0 as u32).min(self.size.0.saturating_sub(1)),
+            (self.mouse.pos.1 as u32).min(self.size.1.saturating_sub(1)),
+        )
+    }
+    /// Get the mouse position on the screen, in pixels but with the fraction of pixel the cursor
+    /// is on. It goes from 0 to the size of the screen, included
+    pub fn get_mouse_position(&self) -> (f32, f32) {
         self.mouse.pos
     }
+    /// Get how far the mouse moved on the screen during the frame, in pixels. The first position
+    /// after the cursor entered the window isn't a movement
+    pub fn get_mouse_delta(&self) -> (f32, f32) {
+        self.mouse.delta
+    }
+    /// Get how far the mouse device moved during the frame, in unspecified units and without the
+    /// acceleration of the cursor. It keeps working when the cursor is
+    /// [locked](inputs::CursorGrab::Locked), for mouselook
+    pub fn get_mouse_raw_delta(&self) -> (f32, f32) {
+        self.mouse.raw_delta
+    }
+    /// Is the mouse cursor above the window
+    pub fn is_mouse_in_window(&self) -> bool {
+        self.mouse.in_window
+    }
     /// Get the scroll wheel direction (If Any) during the frame
     pub fn get_mouse_wheel(&self) -> MouseWheel {
         self.mouse.wheel
     }
+    /// Get how much the wheel scrolled during the frame, in lines. A positive `x` is
+    /// [`MouseWheel::Right`] and a positive `y` is [`MouseWheel::Down`]
+    pub fn get_mouse_scroll(&self) -> (f32, f32) {
+        self.mouse.scroll
+    }
+
+    /// Keep the cursor inside the window, or release it with [`CursorGrab::None`](inputs::CursorGrab::None).
+    /// Does nothing on an headless engine
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the platform can't grab the cursor
+    pub fn set_cursor_grab(&mut self, grab: inputs::CursorGrab) -> Result<(), String> {
+        use winit::window::CursorGrabMode;
+        let Some(window) = &self.window else {
+            return Ok(());
+        };
+        match grab {
+            inputs::CursorGrab::None => window.set_cursor_grab(CursorGrabMode::None),
+            inputs::CursorGrab::Confined => window.set_cursor_grab(CursorGrabMode::Confined),
+            inputs::CursorGrab::Locked => window
+                .set_cursor_grab(CursorGrabMode::Locked)
+                .or_else(|_| window.set_cursor_grab(CursorGrabMode::Confined)),
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    /// Show or hide the cursor when it is above the window
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        if let Some(window) = &self.window {
+            window.set_cursor_visible(visible);
+        }
+    }
+    /// Get the text typed since the last frame, in order.
+    ///
+    /// Unlike [`Engine::get_pressed`], the keyboard layout, shift, dead keys and IME are taken
+    /// into account. Control characters are left out
+    pub fn get_text_input(&self) -> &str {
+        &self.text_input
+    }
+
+    /// Get the files dropped on the window since the last frame, in order
+    pub fn get_dropped_files(&self) -> &[std::path::PathBuf] {
+        &self.dropped_files
+    }
+
+    /// Get the files being dragged above the window, to show where they would be dropped.
+    /// It is empty when nothing is dragged
+    pub fn get_hovered_files(&self) -> &[std::path::PathBuf] {
+        &self.hovered_files
+    }
+
+    /// Allow the platform's Input Method Editor (to type chinese, japanese... text) on the window,
+    /// it is off by default because it can delay some key events
+    pub fn set_ime_allowed(&mut self, allowed: bool) {
+        if let Some(window) = &self.window {
+            window.set_ime_allowed(allowed);
+        }
+    }
+
     /// Get all Keys pressed during the last frame
     pub fn get_pressed(&self) -> std::collections::HashSet<inputs::Keycodes> {
         self.k_pressed.clone().iter().map(|k| k.key).collect()
     }
 
+    /// Get the frame as it will be presented: the screen with the layers and the decals drawn so
+    /// far on top of it
+    ///
+    /// Call it at the end of the frame to capture everything that was drawn during it.
+    /// In a window, the decals are only there if the engine was built with
+    /// [`EngineBuilder::capture`]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn screenshot(&self) -> Sprite {
+        let frame = layer::with_frame(&self.screen, &self.layers, |raw, layers| {
+            self.handler.composite(raw, layers)
+        });
+        let size = self.screen.get_ref().size();
+        Sprite::load_rgba(&frame, size.x as usize, size.y as usize)
+            .expect("The composited frame has the size of the screen")
+    }
+
+    /// Save a screenshot each time `key` is pressed, as `screenshot_0000.png`,
+    /// `screenshot_0001.png`, ... in the given directory.
+    /// Existing files are never overwritten.
+    ///
+    /// Use `None` to remove the binding
+    pub fn set_screenshot_key<P: Into<std::path::PathBuf>>(
+        &mut self,
+        key: Option<inputs::Keycodes>,
+        directory: P,
+    ) {
+        self.screenshot_key = key.map(|key| (key, directory.into()));
+    }
+
+    fn save_numbered_screenshot(&mut self, directory: &std::path::Path) {
+        let path = next_free_path(directory, "screenshot", ".png", &mut self.screenshot_count);
+        let result = std::fs::create_dir_all(directory)
+            .map_err(|e| e.to_string())
+            .and_then(|()| self.screenshot().save_to_file(&path));
+        if let Err(e) = result {
+            eprintln!("Could not save the screenshot {}: {e}", path.display());
+        }
+    }
+
+    /// Start recording the presented frames at `fps` frames per second, see the
+    /// [`recorder`](super::recorder) module.
+    /// `path` is a file for [`RecordingFormat::Gif`] and a directory for
+    /// [`RecordingFormat::PngSequence`]. A recording in progress is stopped first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the previous recording couldn't be finished or if the new one couldn't
+    /// be created
+    pub fn start_recording<P: Into<std::path::PathBuf>>(
+        &mut self,
+        format: RecordingFormat,
+        path: P,
+        fps: u32,
+    ) -> Result<(), String> {
+        self.stop_recording()?;
+        self.recorder = Some(Recorder::new(format, path, fps)?);
+        Ok(())
+    }
+
+    /// Stop the current recording (if any) and write its last frame
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the last frame couldn't be written
+    pub fn stop_recording(&mut self) -> Result<(), String> {
+        match self.recorder.take() {
+            Some(recorder) => recorder.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Return true if a recording is in progress
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Start a recording when `key` is pressed and stop it when it is pressed again.
+    /// The recordings are named `recording_0000`, `recording_0001`, ... (with a `.gif` extension
+    /// for GIFs) inside the given directory. Existing recordings are never overwritten.
+    ///
+    /// Use `None` to remove the binding, it doesn't stop the current recording
+    pub fn set_recording_key<P: Into<std::path::PathBuf>>(
+        &mut self,
+        key: Option<inputs::Keycodes>,
+        format: RecordingFormat,
+        fps: u32,
+        directory: P,
+    ) {
+        self.recording_key = key.map(|key| (key, format, fps, directory.into()));
+    }
+
+    fn toggle_numbered_recording(
+        &mut self,
+        format: RecordingFormat,
+        fps: u32,
+        directory: &std::path::Path,
+    ) {
+        let result = if self.is_recording() {
+            self.stop_recording()
+        } else {
+            let path = next_free_path(
+                directory,
+                "recording",
+                format.extension(),
+                &mut self.recording_count,
+            );
+            std::fs::create_dir_all(directory)
+                .map_err(|e| e.to_string())
+                .and_then(|()| self.start_recording(format, path, fps))
+        };
+        if let Err(e) = result {
+            eprintln!("Could not toggle the recording: {e}");
+        }
+    }
+
+    /// Start recording the inputs and `elapsed` of every frame, see the [`trace`](super::trace)
+    /// module. A recording in progress is discarded
+    pub fn start_input_recording(&mut self) {
+        self.input_recording = Some((InputTrace::new((self.size.0, self.size.1)), Vec::new()));
+    }
+
+    /// Stop recording the inputs and return the trace, `None` if nothing was being recorded
+    pub fn stop_input_recording(&mut self) -> Option<InputTrace> {
+        self.input_recording.take().map(|(trace, _)| trace)
+    }
+
+    /// Feed the inputs and `elapsed` of the trace to the next frames instead of the real ones.
+    /// Once the trace is over, the real inputs are used again
+    pub fn start_replay(&mut self, trace: InputTrace) {
+        self.replay = Some(trace.frames.into_iter());
+    }
+
+    /// Return true if a trace is being replayed
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
     /// Create a GPU version of [`Sprite`]
     pub fn create_decal(&mut self, sprite: &Sprite) -> Decal {
         Decal::new(&mut self.handler, sprite)
@@ -399,7 +1536,32 @@ impl Engine {
     /// trying to draw the decal afterwards will just not render anything, but may affect
     /// performance if you try to draw lots of "zombie" decals
     pub fn destroy_decal(&mut self, decal: &Decal) {
-        decal.0.destroy(&mut self.handler);
-        decal.1.set(false);
+        decal.destroy(&mut self.handler);
+    }
+}
+
+/// Return `directory/<prefix>_<counter><extension>` for the first counter (starting at `counter`)
+/// that doesn't exist yet, `counter` is left just after it
+fn next_free_path(
+    directory: &std::path::Path,
+    prefix: &str,
+    extension: &str,
+    counter: &mut u32,
+) -> std::path::PathBuf {
+    loop {
+        let path = directory.join(format!("{prefix}_{:04}{extension}", *counter));
+        *counter += 1;
+        if !path.exists() {
+            break path;
+        }
     }
 }
+
+/// Combine the inputs of several bindings: pressed when the first of them is pressed, released
+/// when the last of them is released
+fn combine_inputs(inputs: &[Input]) -> Input {
+    let held = inputs.iter().any(|input| input.held);
+    let pressed = !held && inputs.iter().any(|input| input.pressed);
+    let released = !held && !pressed && inputs.iter().any(|input| input.released);
+    Input::new(pressed, held, released).with_repeated(inputs.iter().any(|input| input.repeated))
+}
@@ -1,18 +1,33 @@
 #![allow(clippy::module_name_repetitions)]
 pub use px_draw::graphics;
+pub use px_draw::transform;
 pub use px_draw::vector2;
 /// A collection of traits used to draw things
 pub mod traits;
 
+pub mod actions;
+mod builder;
 /// A Gpu-located spite
 pub mod decals;
+pub mod events;
+mod game;
+pub mod gamepad;
+mod handler;
 /// User Input module
 pub mod inputs;
+mod layer;
 mod logic;
+pub mod recorder;
 mod screen;
+pub mod testing;
+pub mod timestep;
+pub mod trace;
 pub use graphics::{Color, PixelMode, Sprite};
 
-pub use logic::{Engine, EngineWrapper};
+pub use builder::{EngineBuilder, PresentMode, Scaling, UnfocusedMode, WindowMode};
+pub use game::Game;
+pub use layer::Layer;
+pub use logic::{Engine, EngineWrapper, HEADLESS_ELAPSED};
 
 /// Takes a future and run it in the context of the engine
 /// This is usefull when targeting wasm32 because we can't use the futures' `block_on` method
@@ -25,5 +40,5 @@ pub fn launch<F: 'static + std::future::Future<Output = ()>>(f: F) {
         crate::wasm_bindgen_futures::spawn_local(f);
     };
     #[cfg(not(target_arch = "wasm32"))]
-    crate::futures::executor::block_on(f);
+    futures::executor::block_on(f);
 }
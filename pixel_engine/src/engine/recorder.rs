@@ -0,0 +1,197 @@
+//! Record the presented frames as an animated GIF or as a numbered PNG sequence.
+//!
+//! The recording is sampled at a fixed frame rate using the engine's `elapsed`, so it plays back
+//! at the same speed as the game did, no matter how fast the game actually ran.
+//!
+//! A recording is started with [`Engine::start_recording`](super::Engine::start_recording) and
+//! finished with [`Engine::stop_recording`](super::Engine::stop_recording), or toggled by a key
+//! bound with [`Engine::set_recording_key`](super::Engine::set_recording_key).
+
+use super::Sprite;
+use std::convert::TryFrom;
+use std::path::PathBuf;
+
+/// How the frames of a recording are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// A single animated GIF, looping forever.
+    /// GIF delays are in hundredths of second, so the frame rate is capped at 50
+    Gif,
+    /// A directory filled with `frame_00000.png`, `frame_00001.png`, ...
+    /// one file per recorded frame
+    PngSequence,
+}
+
+impl RecordingFormat {
+    /// Extension of a recording in this format, empty for a directory
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            RecordingFormat::Gif => ".gif",
+            RecordingFormat::PngSequence => "",
+        }
+    }
+}
+
+enum Encoder {
+    Gif {
+        encoder: image::codecs::gif::GifEncoder<std::io::BufWriter<std::fs::File>>,
+        // Delay already written, in hundredths of second
+        written: u64,
+    },
+    PngSequence {
+        directory: PathBuf,
+    },
+}
+
+/// Samples the frames it is given at a fixed rate and encodes them
+pub struct Recorder {
+    encoder: Encoder,
+    fps: u32,
+    /// Time at which the last frame was presented, from the start of the recording
+    time: f64,
+    /// Number of ticks (`1/fps` seconds) already written, the pending frame starts at this tick
+    ticks: u64,
+    pending: Option<Sprite>,
+}
+
+impl std::fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Recorder")
+            .field("fps", &self.fps)
+            .field("time", &self.time)
+            .field("ticks", &self.ticks)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Recorder {
+    /// Create a recorder writing to `path`, which is a file for [`RecordingFormat::Gif`] and a
+    /// directory for [`RecordingFormat::PngSequence`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file or directory couldn't be created
+    pub fn new<P: Into<PathBuf>>(
+        format: RecordingFormat,
+        path: P,
+        fps: u32,
+    ) -> Result<Self, String> {
+        let path = path.into();
+        let (encoder, fps) = match format {
+            RecordingFormat::Gif => {
+                let file = std::fs::File::create(&path).map_err(|e| e.to_string())?;
+                // The default speed (1) gives a slightly better palette but is way too slow to
+                // encode while the game is running
+                let mut encoder = image::codecs::gif::GifEncoder::new_with_speed(
+                    std::io::BufWriter::new(file),
+                    10,
+                );
+                encoder
+                    .set_repeat(image::codecs::gif::Repeat::Infinite)
+                    .map_err(|e| e.to_string())?;
+                (
+                    Encoder::Gif {
+                        encoder,
+                        written: 0,
+                    },
+                    fps.clamp(1, 50),
+                )
+            }
+            RecordingFormat::PngSequence => {
+                std::fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+                (Encoder::PngSequence { directory: path }, fps.max(1))
+            }
+        };
+        Ok(Self {
+            encoder,
+            fps,
+            time: 0.0,
+            ticks: 0,
+            pending: None,
+        })
+    }
+
+    /// A frame was just presented, `elapsed` being the time since the previous one.
+    ///
+    /// `frame` is only called to get the frame when it is kept: the first one, then the first
+    /// one presented after each tick, so the screen isn't read back every frame
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a frame couldn't be encoded
+    pub fn capture(&mut self, elapsed: f64, frame: impl FnOnce() -> Sprite) -> Result<(), String> {
+        if let Some(pending) = self.pending.take() {
+            self.time += elapsed.max(0.0);
+            let tick = self.tick_at(self.time);
+            if tick <= self.ticks {
+                self.pending = Some(pending);
+                return Ok(());
+            }
+            // The pending frame covers every tick before the current one
+            self.write(&pending, tick)?;
+        }
+        self.pending = Some(frame());
+        Ok(())
+    }
+
+    /// Write the last kept frame until the time of the last presented frame, and flush
+    /// everything
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the last frame couldn't be encoded
+    pub fn finish(mut self) -> Result<(), String> {
+        if let Some(pending) = self.pending.take() {
+            let end = self.tick_at(self.time) + 1;
+            self.write(&pending, end)?;
+        }
+        if let Encoder::Gif { encoder, .. } = self.encoder {
+            drop(encoder);
+        }
+        Ok(())
+    }
+
+    /// The tick the time falls in, the epsilon puts a time exactly on a tick (give or take
+    /// rounding) in that tick
+    fn tick_at(&self, time: f64) -> u64 {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let tick = (time * f64::from(self.fps) + 1e-6).floor() as u64;
+        tick
+    }
+
+    /// Write `frame` for every tick until `end`
+    fn write(&mut self, frame: &Sprite, end: u64) -> Result<(), String> {
+        match &mut self.encoder {
+            Encoder::Gif { encoder, written } => {
+                // Round the end time rather than each delay, so the errors don't add up
+                let target = (end * 100 + u64::from(self.fps) / 2) / u64::from(self.fps);
+                let delay = u32::try_from(target - *written).map_err(|e| e.to_string())?;
+                *written = target;
+                let (raw, lock) = frame.get_read_lock();
+                let buffer =
+                    image::RgbaImage::from_raw(frame.width(), frame.height(), raw.to_vec());
+                drop(lock);
+                let buffer = buffer.ok_or_else(|| "Wrong Image len".to_string())?;
+                encoder
+                    .encode_frame(image::Frame::from_parts(
+                        buffer,
+                        0,
+                        0,
+                        image::Delay::from_numer_denom_ms(delay * 10, 1),
+                    ))
+                    .map_err(|e| e.to_string())?;
+            }
+            Encoder::PngSequence { directory } => {
+                let first = directory.join(format!("frame_{:05}.png", self.ticks));
+                frame.save_to_file(&first)?;
+                // The game was slower than the recording, repeat the frame
+                for tick in self.ticks + 1..end {
+                    std::fs::copy(&first, directory.join(format!("frame_{tick:05}.png")))
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        self.ticks = end;
+        Ok(())
+    }
+}
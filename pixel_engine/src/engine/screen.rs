@@ -2,27 +2,27 @@ use px_draw::traits::SmartDrawingTrait;
 
 impl px_draw::graphics::DrawSpriteTrait for crate::Engine {
     fn size(&self) -> pixel_engine_draw::vector2::Vu2d {
-        self.screen.size()
+        self.target().size()
     }
 
     fn get_pixel(
         &self,
         pos: pixel_engine_draw::vector2::Vi2d,
     ) -> Option<pixel_engine_draw::graphics::Color> {
-        px_draw::graphics::DrawSpriteTrait::get_pixel(&self.screen, pos)
+        px_draw::graphics::DrawSpriteTrait::get_pixel(self.target(), pos)
     }
     fn set_pixel(
         &mut self,
         pos: pixel_engine_draw::vector2::Vi2d,
         col: pixel_engine_draw::graphics::Color,
     ) {
-        self.screen.set_pixel(pos, col);
+        self.target_mut().set_pixel(pos, col);
     }
     unsafe fn get_pixel_unchecked(
         &self,
         pos: pixel_engine_draw::vector2::Vu2d,
     ) -> pixel_engine_draw::graphics::Color {
-        self.screen.get_pixel_unchecked(pos)
+        self.target().get_pixel_unchecked(pos)
     }
 
     unsafe fn set_pixel_unchecked(
@@ -30,7 +30,7 @@ impl px_draw::graphics::DrawSpriteTrait for crate::Engine {
         pos: pixel_engine_draw::vector2::Vu2d,
         col: pixel_engine_draw::graphics::Color,
     ) {
-        self.screen.set_pixel_unchecked(pos, col);
+        self.target_mut().set_pixel_unchecked(pos, col);
     }
 }
 
@@ -40,41 +40,66 @@ impl SmartDrawingTrait for crate::Engine {
         pos: P,
         col: pixel_engine_draw::graphics::Color,
     ) {
-        self.screen.draw(pos, col);
+        self.target_mut().draw(pos, col);
+    }
+
+    fn push_clip<P: Into<pixel_engine_draw::vector2::Vi2d>>(&mut self, pos: P, size: P) {
+        self.target_mut().push_clip(pos, size);
+    }
+
+    fn pop_clip(&mut self) {
+        self.target_mut().pop_clip();
+    }
+
+    fn push_transform<P: Into<pixel_engine_draw::vector2::Vi2d>>(&mut self, offset: P, scale: f32) {
+        self.target_mut().push_transform(offset, scale);
+    }
+
+    fn pop_transform(&mut self) {
+        self.target_mut().pop_transform();
+    }
+
+    fn get_clip(
+        &self,
+    ) -> (
+        pixel_engine_draw::vector2::Vi2d,
+        pixel_engine_draw::vector2::Vi2d,
+    ) {
+        self.target().get_clip()
     }
 
     fn clear(&mut self, col: pixel_engine_draw::graphics::Color) {
-        self.screen.clear(col);
+        self.target_mut().clear(col);
     }
 
     fn get_size(&self) -> pixel_engine_draw::vector2::Vu2d {
-        self.screen.get_size()
+        self.target().get_size()
     }
 
     fn set_pixel_mode(&mut self, mode: pixel_engine_draw::graphics::PixelMode) {
-        self.screen.set_pixel_mode(mode);
+        self.target_mut().set_pixel_mode(mode);
     }
 
     fn get_pixel<P: Into<pixel_engine_draw::vector2::Vi2d>>(
         &self,
         pos: P,
     ) -> Option<pixel_engine_draw::graphics::Color> {
-        self.screen.get_pixel(pos)
+        self.target().get_pixel(pos)
     }
 
     fn set_blend_factor(&mut self, f: f32) {
-        self.screen.set_blend_factor(f);
+        self.target_mut().set_blend_factor(f);
     }
 
     fn get_textsheet(&self) -> &'static pixel_engine_draw::graphics::Sprite {
-        self.screen.get_textsheet()
+        self.target().get_textsheet()
     }
 
     fn get_pixel_mode(&self) -> pixel_engine_draw::graphics::PixelMode {
-        self.screen.get_pixel_mode()
+        self.target().get_pixel_mode()
     }
 
     fn get_blend_factor(&self) -> f32 {
-        self.screen.get_blend_factor()
+        self.target().get_blend_factor()
     }
 }
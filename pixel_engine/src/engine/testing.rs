@@ -0,0 +1,290 @@
+//! Helpers to test games and drawing code without a window.
+//!
+//! A game can be run for a given number of frames with [`run_frames`], while an [`InputScript`]
+//! feeds it inputs, and the resulting frame can be compared to a reference PNG with
+//! [`assert_golden`].
+//!
+//! ```no_run
+//! use pixel_engine::testing::{assert_golden, run_frames, InputScript};
+//! use pixel_engine::inputs::{InputEvent, Keycodes};
+//! use pixel_engine::traits::*;
+//!
+//! let script = InputScript::new().at(1, InputEvent::KeyPress(Keycodes::Space));
+//! let frame = run_frames((32, 32), 3, &script, |engine| {
+//!     engine.clear(pixel_engine::Color::BLACK);
+//!     if engine.get_key(Keycodes::Space).held {
+//!         engine.draw_line((0, 0), (31, 31), pixel_engine::Color::WHITE);
+//!     }
+//!     Ok(true)
+//! })
+//! .unwrap();
+//! assert_golden(&frame, "tests/golden/space_held.png", 0);
+//! ```
+//!
+//! When a reference doesn't exist, or when the `PIXEL_ENGINE_BLESS` environment variable is set,
+//! the current frame is written as the new reference.
+//! When a comparison fails, `<name>.actual.png`, `<name>.expected.png` and `<name>.diff.png` are
+//! written next to the reference, they are removed by the next comparison.
+
+use super::events::Event;
+use super::inputs::{InputEvent, Keycodes};
+use super::trace::InputTrace;
+use super::{Color, Engine, EngineWrapper, Sprite};
+use std::path::{Path, PathBuf};
+
+/// Environment variable that, when set, makes [`check_golden`] overwrite the references
+pub const BLESS_ENV_VAR: &str = "PIXEL_ENGINE_BLESS";
+
+/// A list of inputs, each one happening at a given frame (starting at 0)
+#[derive(Debug, Clone, Default)]
+pub struct InputScript {
+    inputs: Vec<(usize, Event)>,
+}
+
+impl InputScript {
+    /// Create an empty script
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an input happening at the start of the given frame
+    #[must_use]
+    pub fn at(self, frame: usize, input: InputEvent) -> Self {
+        self.event(frame, Event::Input(input))
+    }
+
+    /// Add an event (a file dropped, the window resized...) happening at the start of the given
+    /// frame
+    #[must_use]
+    pub fn event(mut self, frame: usize, event: Event) -> Self {
+        self.inputs.push((frame, event));
+        self
+    }
+
+    /// Press the key at `frame` and release it `duration` frames later
+    #[must_use]
+    pub fn key_tap(self, frame: usize, duration: usize, key: Keycodes) -> Self {
+        self.at(frame, InputEvent::KeyPress(key))
+            .at(frame + duration, InputEvent::KeyRelease(key))
+    }
+
+    /// Feed all inputs of the given frame to the engine
+    fn apply(&self, frame: usize, engine: &mut Engine) {
+        for (_, event) in self.inputs.iter().filter(|(f, _)| *f == frame) {
+            engine.handle_event(event.clone());
+        }
+    }
+}
+
+/// Run `main_func` for at most `frames` frames on an headless engine of the given size, feeding
+/// it the inputs of the `script`
+///
+/// Returns the last presented frame
+///
+/// # Errors
+///
+/// Returns the error of the main function if it returned one.
+pub fn run_frames<F>(
+    size: (u32, u32),
+    frames: usize,
+    script: &InputScript,
+    mut main_func: F,
+) -> Result<Sprite, Box<dyn std::error::Error>>
+where
+    F: FnMut(&mut Engine) -> Result<bool, Box<dyn std::error::Error>>,
+{
+    let mut frame = 0;
+    EngineWrapper::new_headless(size).run_headless(frames, |engine| {
+        script.apply(frame, engine);
+        frame += 1;
+        main_func(engine)
+    })
+}
+
+/// Replay an [`InputTrace`] on an headless engine of the size it was recorded with, running one
+/// frame per recorded frame
+///
+/// Returns the last presented frame
+///
+/// # Errors
+///
+/// Returns the error of the main function if it returned one.
+pub fn replay<F>(trace: &InputTrace, main_func: F) -> Result<Sprite, Box<dyn std::error::Error>>
+where
+    F: FnMut(&mut Engine) -> Result<bool, Box<dyn std::error::Error>>,
+{
+    let mut wrapper = EngineWrapper::new_headless(trace.size);
+    wrapper.start_replay(trace.clone());
+    wrapper.run_headless(trace.frames.len(), main_func)
+}
+
+/// Error returned when a frame doesn't match its reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoldenError {
+    /// The reference didn't exist, it has been created from the actual frame
+    MissingReference(PathBuf),
+    /// The images don't have the same size
+    SizeMismatch {
+        /// Size of the actual frame
+        actual: (u32, u32),
+        /// Size of the reference
+        expected: (u32, u32),
+    },
+    /// Some pixels are different by more than the tolerance
+    PixelMismatch {
+        /// Number of pixels that are too different
+        count: usize,
+        /// Coordinates of the first one
+        first: (u32, u32),
+    },
+    /// Reading or writing an image failed
+    Image(String),
+}
+
+impl std::fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoldenError::MissingReference(path) => {
+                write!(
+                    f,
+                    "Reference {} didn't exist, it was created",
+                    path.display()
+                )
+            }
+            GoldenError::SizeMismatch { actual, expected } => write!(
+                f,
+                "Size mismatch: got {}x{}, expected {}x{}",
+                actual.0, actual.1, expected.0, expected.1
+            ),
+            GoldenError::PixelMismatch { count, first } => write!(
+                f,
+                "{} pixel(s) differ, the first one at ({}, {})",
+                count, first.0, first.1
+            ),
+            GoldenError::Image(e) => write!(f, "Image error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for GoldenError {}
+
+/// Compare two sprites, two pixels match if none of their channel differ by more than
+/// `tolerance`
+///
+/// # Errors
+///
+/// Returns [`GoldenError::SizeMismatch`] or [`GoldenError::PixelMismatch`] if they don't match
+pub fn compare(actual: &Sprite, expected: &Sprite, tolerance: u8) -> Result<(), GoldenError> {
+    if actual.size() != expected.size() {
+        return Err(GoldenError::SizeMismatch {
+            actual: (actual.width(), actual.height()),
+            expected: (expected.width(), expected.height()),
+        });
+    }
+    let mut count = 0;
+    let mut first = None;
+    for y in 0..actual.height() {
+        for x in 0..actual.width() {
+            if !pixel_match(actual.get_pixel(x, y), expected.get_pixel(x, y), tolerance) {
+                count += 1;
+                first.get_or_insert((x, y));
+            }
+        }
+    }
+    match first {
+        None => Ok(()),
+        Some(first) => Err(GoldenError::PixelMismatch { count, first }),
+    }
+}
+
+/// Compare `actual` against the PNG at `reference`, see the [module documentation](self)
+///
+/// # Errors
+///
+/// Returns an error if the images don't match, if the reference was missing or if an image
+/// couldn't be read or written.
+pub fn check_golden<P: AsRef<Path>>(
+    actual: &Sprite,
+    reference: P,
+    tolerance: u8,
+) -> Result<(), GoldenError> {
+    let reference = reference.as_ref();
+    let bless = std::env::var_os(BLESS_ENV_VAR).is_some();
+    if bless || !reference.exists() {
+        if let Some(parent) = reference.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| GoldenError::Image(e.to_string()))?;
+        }
+        save(actual, reference)?;
+        return if bless {
+            Ok(())
+        } else {
+            Err(GoldenError::MissingReference(reference.to_path_buf()))
+        };
+    }
+    let expected = Sprite::load_from_file(reference).map_err(GoldenError::Image)?;
+    let result = compare(actual, &expected, tolerance);
+    // The files of an earlier failure would describe another run
+    for suffix in ["actual", "expected", "diff"] {
+        let path = with_suffix(reference, suffix);
+        if path.exists() {
+            std::fs::remove_file(path).map_err(|e| GoldenError::Image(e.to_string()))?;
+        }
+    }
+    if result.is_err() {
+        save(actual, &with_suffix(reference, "actual"))?;
+        save(&expected, &with_suffix(reference, "expected"))?;
+        if actual.size() == expected.size() {
+            save(
+                &diff(actual, &expected, tolerance),
+                &with_suffix(reference, "diff"),
+            )?;
+        }
+    }
+    result
+}
+
+/// Same as [`check_golden`] but panics if the images don't match
+///
+/// # Panics
+///
+/// Panics if [`check_golden`] returned an error
+#[track_caller]
+pub fn assert_golden<P: AsRef<Path>>(actual: &Sprite, reference: P, tolerance: u8) {
+    if let Err(e) = check_golden(actual, reference.as_ref(), tolerance) {
+        panic!("Golden image {}: {}", reference.as_ref().display(), e);
+    }
+}
+
+fn pixel_match(a: Color, b: Color, tolerance: u8) -> bool {
+    a.r.abs_diff(b.r) <= tolerance
+        && a.g.abs_diff(b.g) <= tolerance
+        && a.b.abs_diff(b.b) <= tolerance
+        && a.a.abs_diff(b.a) <= tolerance
+}
+
+/// Mismatching pixels are red, matching ones are a darker version of the expected pixel
+fn diff(actual: &Sprite, expected: &Sprite, tolerance: u8) -> Sprite {
+    let mut diff = Sprite::new(actual.width(), actual.height());
+    for y in 0..actual.height() {
+        for x in 0..actual.width() {
+            let e = expected.get_pixel(x, y);
+            if pixel_match(actual.get_pixel(x, y), e, tolerance) {
+                diff.set_pixel(x, y, Color::new(e.r / 4, e.g / 4, e.b / 4));
+            } else {
+                diff.set_pixel(x, y, Color::RED);
+            }
+        }
+    }
+    diff
+}
+
+/// `dir/name.png` => `dir/name.<suffix>.png`
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}.{suffix}.png"))
+}
+
+fn save(sprite: &Sprite, path: &Path) -> Result<(), GoldenError> {
+    sprite.save_to_file(path).map_err(GoldenError::Image)
+}
@@ -0,0 +1,122 @@
+//! Run the game logic at a fixed rate, independently of the frame rate.
+//!
+//! [`FixedTimestep`] accumulates the `elapsed` of every frame and calls an update function once
+//! per tick of `1/tick_rate` seconds, then calls a draw function once with an interpolation
+//! `alpha` (how far, between 0 and 1, the current time is between the last tick and the next one).
+//!
+//! ```no_run
+//! use pixel_engine::timestep::FixedTimestep;
+//! use pixel_engine::traits::*;
+//!
+//! pixel_engine::launch(async move {
+//!     let wrapper = pixel_engine::EngineWrapper::new("Fixed".to_owned(), (64, 64, 4)).await;
+//!     let (mut previous, mut current) = (0.0, 0.0);
+//!     wrapper.run_fixed(
+//!         FixedTimestep::new(50),
+//!         move |_engine, dt| {
+//!             previous = current;
+//!             current += 20.0 * dt;
+//!             Ok(true)
+//!         },
+//!         move |engine, alpha| {
+//!             let x = previous + (current - previous) * alpha;
+//!             engine.clear(pixel_engine::Color::BLACK);
+//!             engine.draw((x as i32 % 64, 32), pixel_engine::Color::WHITE);
+//!             Ok(true)
+//!         },
+//!     );
+//! });
+//! ```
+//!
+//! Pressed and released inputs only last one frame, which may contain no tick at all:
+//! edge-triggered inputs are safer to read in the draw function.
+
+use super::Engine;
+
+/// Accumulator driving the fixed rate updates, see the [module documentation](self)
+#[derive(Debug, Clone, Copy)]
+pub struct FixedTimestep {
+    dt: f64,
+    max_updates: u32,
+    max_frame_time: f64,
+    accumulator: f64,
+}
+
+impl FixedTimestep {
+    /// Call the update function `tick_rate` times per second
+    ///
+    /// By default at most 8 updates are run per frame and frames are considered to last at most
+    /// 0.25 seconds
+    #[must_use]
+    pub fn new(tick_rate: u32) -> Self {
+        Self {
+            dt: 1.0 / f64::from(tick_rate.max(1)),
+            max_updates: 8,
+            max_frame_time: 0.25,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Maximum number of updates in a single frame. When it is reached, the remaining ticks are
+    /// dropped and the game slows down instead of trying to catch up forever
+    #[must_use]
+    pub fn max_updates_per_frame(mut self, max_updates: u32) -> Self {
+        self.max_updates = max_updates.max(1);
+        self
+    }
+
+    /// Frames longer than this (in seconds) are shortened to it, so a single stall (loading,
+    /// window being dragged, debugger...) doesn't trigger a burst of updates
+    #[must_use]
+    pub fn max_frame_time(mut self, max_frame_time: f64) -> Self {
+        self.max_frame_time = max_frame_time.max(0.0);
+        self
+    }
+
+    /// Duration of a tick, in seconds
+    #[must_use]
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    /// Run one frame: call `update` for every tick that elapsed, then `draw` once with the
+    /// interpolation alpha
+    ///
+    /// During the updates `engine.elapsed` is set to the duration of a tick, it is restored
+    /// before `draw` is called.
+    /// This is what [`EngineWrapper::run_fixed`](super::EngineWrapper::run_fixed) does every
+    /// frame, and can be used to drive the updates from [`EngineWrapper::run_headless`](super::EngineWrapper::run_headless)
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `update` or `draw` if one returned an error.
+    pub fn frame<U, D>(
+        &mut self,
+        engine: &mut Engine,
+        update: &mut U,
+        draw: &mut D,
+    ) -> Result<bool, Box<dyn std::error::Error>>
+    where
+        U: FnMut(&mut Engine, f64) -> Result<bool, Box<dyn std::error::Error>>,
+        D: FnMut(&mut Engine, f64) -> Result<bool, Box<dyn std::error::Error>>,
+    {
+        let elapsed = engine.elapsed;
+        self.accumulator += elapsed.clamp(0.0, self.max_frame_time);
+        let mut updates = 0;
+        while self.accumulator >= self.dt && updates < self.max_updates {
+            engine.elapsed = self.dt;
+            let r = update(engine, self.dt);
+            engine.elapsed = elapsed;
+            if !r? {
+                return Ok(false);
+            }
+            self.accumulator -= self.dt;
+            updates += 1;
+        }
+        if self.accumulator >= self.dt {
+            // Spiral of death: drop the ticks that couldn't be run
+            self.accumulator %= self.dt;
+        }
+        draw(engine, self.accumulator / self.dt)
+    }
+}
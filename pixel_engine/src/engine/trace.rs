@@ -0,0 +1,83 @@
+//! Record the inputs of a game and replay them later.
+//!
+//! An [`InputTrace`] holds, for every frame, its `elapsed` and the inputs that happened before it.
+//! Feeding it back to a game that only depends on its inputs and on `elapsed` reproduces the
+//! recorded run exactly, with a window or headless (see [`testing::replay`](super::testing::replay)).
+//!
+//! ```no_run
+//! # fn game(engine: &mut pixel_engine::Engine) {}
+//! use pixel_engine::trace::InputTrace;
+//!
+//! pixel_engine::launch(async move {
+//!     let mut wrapper = pixel_engine::EngineWrapper::new("Replay".to_owned(), (64, 64, 4)).await;
+//!     if let Ok(trace) = InputTrace::load_from_file("bug_report.ron") {
+//!         wrapper.start_replay(trace);
+//!     } else {
+//!         wrapper.start_input_recording();
+//!     }
+//!     wrapper.run(|engine| {
+//!         game(engine);
+//!         if engine.get_key(pixel_engine::inputs::Keycodes::Escape).pressed {
+//!             if let Some(trace) = engine.stop_input_recording() {
+//!                 trace.save_to_file("bug_report.ron")?;
+//!             }
+//!             return Ok(false);
+//!         }
+//!         Ok(true)
+//!     });
+//! });
+//! ```
+
+use super::inputs::InputEvent;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The inputs of a single frame
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FrameInputs {
+    /// The `elapsed` of the frame
+    pub elapsed: f64,
+    /// The inputs that happened before the frame, in order
+    pub inputs: Vec<InputEvent>,
+}
+
+/// The inputs of every frame of a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputTrace {
+    /// Size of the engine (in pixel) when the trace was recorded, mouse positions depend on it
+    pub size: (u32, u32),
+    /// One entry per frame
+    pub frames: Vec<FrameInputs>,
+}
+
+impl InputTrace {
+    /// Create an empty trace for an engine of the given size
+    #[must_use]
+    pub fn new(size: (u32, u32)) -> Self {
+        Self {
+            size,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Load a trace saved with [`InputTrace::save_to_file`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or isn't a valid trace
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        ron::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    /// Save the trace as a RON file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,84 @@
+extern crate pixel_engine as px;
+
+mod common;
+
+use px::actions::{ActionMap, Binding};
+use px::inputs::{InputEvent, Keycodes, MouseBtn, MouseWheel};
+use px::testing::InputScript;
+
+#[test]
+fn action_map() {
+    let actions = ActionMap::new()
+        .bind("jump", Binding::Key(Keycodes::Space))
+        .bind("jump", Binding::Mouse(MouseBtn::Left))
+        .bind("zoom", Binding::Wheel(MouseWheel::Up))
+        .bind_axis("turn", Binding::Key(Keycodes::Q), Binding::Key(Keycodes::D))
+        .bind_axis_2d(
+            "move",
+            [Keycodes::A, Keycodes::D, Keycodes::W, Keycodes::S].map(Binding::Key),
+        );
+    let path = std::env::temp_dir().join(format!("px_actions_{}.ron", std::process::id()));
+    actions.save_to_file(&path).unwrap();
+    let loaded = ActionMap::load_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, actions);
+
+    let script = InputScript::new()
+        .key_tap(1, 3, Keycodes::Space)
+        .at(2, InputEvent::MousePress(MouseBtn::Left))
+        .at(5, InputEvent::MouseRelease(MouseBtn::Left))
+        .at(6, InputEvent::MouseWheel(MouseWheel::Up))
+        .key_tap(6, 2, Keycodes::D)
+        .at(7, InputEvent::KeyPress(Keycodes::S));
+    let seen = common::observe(8, &script, |engine| {
+        if engine.actions().buttons.is_empty() {
+            engine.set_actions(loaded.clone());
+        }
+        let jump = engine.get_action("jump");
+        (
+            (jump.pressed, jump.held, jump.released),
+            engine.get_action("zoom").pressed,
+            engine.get_axis("turn"),
+            engine.get_axis_2d("move"),
+            engine.get_pressed_binding(),
+        )
+    });
+    let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+    assert_eq!(
+        seen,
+        vec![
+            ((false, false, false), false, 0.0, (0.0, 0.0), None),
+            (
+                (true, false, false),
+                false,
+                0.0,
+                (0.0, 0.0),
+                Some(Binding::Key(Keycodes::Space))
+            ),
+            (
+                (false, true, false),
+                false,
+                0.0,
+                (0.0, 0.0),
+                Some(Binding::Mouse(MouseBtn::Left))
+            ),
+            ((false, true, false), false, 0.0, (0.0, 0.0), None),
+            ((false, true, false), false, 0.0, (0.0, 0.0), None),
+            ((false, false, true), false, 0.0, (0.0, 0.0), None),
+            (
+                (false, false, false),
+                true,
+                1.0,
+                (1.0, 0.0),
+                Some(Binding::Wheel(MouseWheel::Up))
+            ),
+            (
+                (false, false, false),
+                false,
+                1.0,
+                (diagonal, diagonal),
+                Some(Binding::Key(Keycodes::S))
+            ),
+        ]
+    );
+}
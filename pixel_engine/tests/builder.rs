@@ -0,0 +1,77 @@
+extern crate pixel_engine as px;
+
+use px::events::Event;
+use px::testing::{run_frames, InputScript};
+use px::vector2::Vu2d;
+use px::{Color, EngineBuilder, PresentMode, Scaling, Sprite, UnfocusedMode, WindowMode};
+
+#[test]
+fn builder_defaults() {
+    let mut wrapper = EngineBuilder::new("Defaults", (16, 8)).build_headless();
+    let engine = &mut *wrapper;
+    assert_eq!(engine.size(), Vu2d { x: 16, y: 8 });
+    assert_eq!(engine.get_max_fps(), None);
+    assert_eq!(engine.get_scaling(), Scaling::Integer);
+    assert_eq!(engine.get_unfocused_mode(), UnfocusedMode::Run);
+    assert_eq!(engine.get_pixel_size(), 1);
+    assert!(!engine.is_resizable());
+    assert_eq!(engine.get_window_mode(), WindowMode::Windowed);
+    assert_eq!(engine.get_present_mode(), PresentMode::Fifo);
+    assert_eq!(engine.get_window_position(), None);
+    assert!(engine.get_icon().is_none());
+}
+
+#[test]
+fn builder_options() {
+    let wrapper = EngineBuilder::new("Options", (8, 8))
+        .size((12, 6))
+        .max_fps(Some(30))
+        .scaling(Scaling::Fractional)
+        .unfocused_mode(UnfocusedMode::Throttle(5))
+        .build_headless();
+    assert_eq!(wrapper.size(), Vu2d { x: 12, y: 6 });
+    assert_eq!(wrapper.get_max_fps(), Some(30));
+    assert_eq!(wrapper.get_scaling(), Scaling::Fractional);
+    assert_eq!(wrapper.get_unfocused_mode(), UnfocusedMode::Throttle(5));
+    // No frame rate at all means no cap
+    let wrapper = EngineBuilder::new("Uncapped", (8, 8))
+        .max_fps(Some(0))
+        .build_headless();
+    assert_eq!(wrapper.get_max_fps(), None);
+}
+
+#[test]
+fn builder_window_options() {
+    let icon = Sprite::new_with_color(2, 2, Color::RED);
+    let wrapper = EngineBuilder::new("Window", (8, 8))
+        .pixel_size(3)
+        .resizable(true)
+        .window_mode(WindowMode::Borderless)
+        .vsync(false)
+        .position((20, -5))
+        .icon(&icon)
+        .build_headless();
+    assert_eq!(wrapper.get_pixel_size(), 3);
+    assert!(wrapper.is_resizable());
+    assert_eq!(wrapper.get_window_mode(), WindowMode::Borderless);
+    assert_eq!(wrapper.get_present_mode(), PresentMode::AutoNoVsync);
+    assert_eq!(wrapper.get_window_position(), Some((20, -5)));
+    let kept = wrapper.get_icon().unwrap();
+    assert_eq!((kept.width(), kept.height()), (2, 2));
+    assert_eq!(kept.get_pixel(1, 1), Color::RED);
+    // The frames stay at the size of the screen, whatever the size of the pixels
+    assert_eq!(wrapper.size(), Vu2d { x: 8, y: 8 });
+}
+
+#[test]
+fn headless_engine_never_pauses() {
+    let script = InputScript::new().event(1, Event::Focused(false));
+    let mut in_background = Vec::new();
+    run_frames((8, 8), 4, &script, |engine| {
+        engine.set_unfocused_mode(UnfocusedMode::Pause);
+        in_background.push(engine.is_in_background());
+        Ok(true)
+    })
+    .unwrap();
+    assert_eq!(in_background, vec![false, true, true, true]);
+}
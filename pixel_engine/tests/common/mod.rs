@@ -0,0 +1,17 @@
+use px::testing::{run_frames, InputScript};
+
+/// Run an 8x8 headless engine for `frames` frames with the inputs of `script`, and collect what
+/// `read` gets from the engine at every frame
+pub fn observe<T>(
+    frames: usize,
+    script: &InputScript,
+    mut read: impl FnMut(&mut px::Engine) -> T,
+) -> Vec<T> {
+    let mut seen = Vec::new();
+    run_frames((8, 8), frames, script, |engine| {
+        seen.push(read(engine));
+        Ok(true)
+    })
+    .unwrap();
+    seen
+}
@@ -0,0 +1,172 @@
+extern crate pixel_engine as px;
+
+mod common;
+
+use px::events::Event;
+use px::gamepad::{GamepadButton, GamepadEvent};
+use px::inputs::{InputEvent, Keycodes, Modifiers, MouseBtn};
+use px::testing::InputScript;
+
+#[test]
+fn ordered_events_and_taps_within_a_frame() {
+    let script = InputScript::new()
+        .at(1, InputEvent::KeyPress(Keycodes::A))
+        .at(1, InputEvent::Character('a'))
+        .at(1, InputEvent::KeyRelease(Keycodes::A))
+        .at(1, InputEvent::MousePress(MouseBtn::Left))
+        .at(1, InputEvent::MouseRelease(MouseBtn::Left))
+        .at(
+            1,
+            InputEvent::Gamepad(GamepadEvent::ButtonPressed(0, GamepadButton::South)),
+        )
+        .at(
+            1,
+            InputEvent::Gamepad(GamepadEvent::ButtonReleased(0, GamepadButton::South)),
+        )
+        .at(2, InputEvent::KeyPress(Keycodes::B))
+        .at(2, InputEvent::KeyPress(Keycodes::B));
+    let seen = common::observe(4, &script, |engine| {
+        let a = engine.get_key(Keycodes::A);
+        let left = engine.get_mouse_btn(MouseBtn::Left);
+        let south = engine.get_gamepad_button(0, GamepadButton::South);
+        (
+            engine.events().to_vec(),
+            [
+                (a.pressed, a.held, a.released),
+                (left.pressed, left.held, left.released),
+                (south.pressed, south.held, south.released),
+            ],
+            engine.get_key(Keycodes::B).held,
+        )
+    });
+    let none = [(false, false, false); 3];
+    assert_eq!(
+        seen,
+        vec![
+            (vec![], none, false),
+            (
+                vec![
+                    Event::Input(InputEvent::KeyPress(Keycodes::A)),
+                    Event::Input(InputEvent::Character('a')),
+                    Event::Input(InputEvent::KeyRelease(Keycodes::A)),
+                    Event::Input(InputEvent::MousePress(MouseBtn::Left)),
+                    Event::Input(InputEvent::MouseRelease(MouseBtn::Left)),
+                    Event::Input(InputEvent::Gamepad(GamepadEvent::ButtonPressed(
+                        0,
+                        GamepadButton::South
+                    ))),
+                    Event::Input(InputEvent::Gamepad(GamepadEvent::ButtonReleased(
+                        0,
+                        GamepadButton::South
+                    ))),
+                ],
+                [(true, false, true); 3],
+                false
+            ),
+            (
+                vec![
+                    Event::Input(InputEvent::KeyPress(Keycodes::B)),
+                    Event::Input(InputEvent::KeyRepeat(Keycodes::B)),
+                ],
+                none,
+                false
+            ),
+            (vec![], none, true),
+        ]
+    );
+}
+
+#[test]
+fn dragged_and_dropped_files() {
+    let script = InputScript::new()
+        .event(1, Event::HoveredFile("map.ron".into()))
+        .event(1, Event::HoveredFile("tile.png".into()))
+        .event(2, Event::DroppedFile("map.ron".into()))
+        .event(2, Event::DroppedFile("tile.png".into()))
+        .event(3, Event::HoveredFile("other.png".into()))
+        .event(4, Event::HoveredFileCancelled);
+    let seen = common::observe(5, &script, |engine| {
+        (
+            engine.get_hovered_files().to_vec(),
+            engine.get_dropped_files().to_vec(),
+        )
+    });
+    let paths = |paths: &[&str]| -> Vec<std::path::PathBuf> {
+        paths.iter().map(std::path::PathBuf::from).collect()
+    };
+    assert_eq!(
+        seen,
+        vec![
+            (paths(&[]), paths(&[])),
+            (paths(&["map.ron", "tile.png"]), paths(&[])),
+            (paths(&[]), paths(&["map.ron", "tile.png"])),
+            (paths(&["other.png"]), paths(&[])),
+            (paths(&[]), paths(&[])),
+        ]
+    );
+}
+
+#[test]
+fn focus_loss_releases_held_inputs() {
+    let script = InputScript::new()
+        .at(1, InputEvent::KeyPress(Keycodes::W))
+        .at(1, InputEvent::KeyPress(Keycodes::LShift))
+        .at(1, InputEvent::MousePress(MouseBtn::Left))
+        .event(3, Event::Focused(false))
+        .event(4, Event::Focused(true));
+    let seen = common::observe(5, &script, |engine| {
+        let w = engine.get_key(Keycodes::W);
+        let left = engine.get_mouse_btn(MouseBtn::Left);
+        if engine.events().first() == Some(&Event::Focused(false)) {
+            assert_eq!(
+                engine.events()[1..],
+                [
+                    Event::Input(InputEvent::KeyRelease(Keycodes::W)),
+                    Event::Input(InputEvent::KeyRelease(Keycodes::LShift)),
+                    Event::Input(InputEvent::MouseRelease(MouseBtn::Left)),
+                ]
+            );
+        }
+        (
+            engine.is_focused(),
+            engine.is_in_background(),
+            (w.held, w.released),
+            (left.held, left.released),
+            engine.get_modifiers(),
+        )
+    });
+    assert_eq!(
+        seen,
+        vec![
+            (
+                true,
+                false,
+                (false, false),
+                (false, false),
+                Modifiers::empty()
+            ),
+            (
+                true,
+                false,
+                (false, false),
+                (false, false),
+                Modifiers::SHIFT
+            ),
+            (true, false, (true, false), (true, false), Modifiers::SHIFT),
+            (
+                false,
+                true,
+                (false, true),
+                (false, true),
+                Modifiers::empty()
+            ),
+            (
+                true,
+                false,
+                (false, false),
+                (false, false),
+                Modifiers::empty()
+            ),
+        ]
+    );
+}
@@ -0,0 +1,118 @@
+extern crate pixel_engine as px;
+
+use px::actions::{ActionMap, Binding};
+use px::gamepad::{GamepadAxis, GamepadButton, VirtualGamepad};
+
+#[test]
+fn virtual_gamepad() {
+    let pad = VirtualGamepad::new(3);
+    let mut wrapper = px::EngineWrapper::new_headless((8, 8));
+    wrapper.add_gamepad_backend(pad.clone());
+    wrapper.set_actions(
+        ActionMap::new()
+            .bind("jump", Binding::GamepadButton(GamepadButton::South))
+            .bind(
+                "right",
+                Binding::GamepadAxis {
+                    axis: GamepadAxis::LeftStickX,
+                    positive: true,
+                },
+            )
+            .bind_axis(
+                "walk",
+                Binding::GamepadAxis {
+                    axis: GamepadAxis::LeftStickX,
+                    positive: false,
+                },
+                Binding::GamepadAxis {
+                    axis: GamepadAxis::LeftStickX,
+                    positive: true,
+                },
+            ),
+    );
+    let mut frame = 0;
+    let mut seen = Vec::new();
+    wrapper
+        .run_headless(6, |engine| {
+            let south = engine.get_gamepad_button(3, GamepadButton::South);
+            let right = engine.get_action("right");
+            seen.push((
+                engine.get_gamepads(),
+                (south.pressed, south.held, south.released),
+                engine.get_action("jump").pressed,
+                engine.get_gamepad_axis(3, GamepadAxis::LeftStickX),
+                engine.get_axis("walk"),
+                (right.pressed, right.held),
+            ));
+            // Inputs given during a frame are seen on the next one
+            match frame {
+                0 => {
+                    pad.press(GamepadButton::South);
+                    pad.set_axis(GamepadAxis::LeftStickX, 0.1);
+                }
+                1 => pad.set_axis(GamepadAxis::LeftStickX, -1.0),
+                2 => {
+                    pad.release(GamepadButton::South);
+                    pad.set_axis(GamepadAxis::LeftStickX, 1.0);
+                }
+                4 => pad.disconnect(),
+                _ => {}
+            }
+            frame += 1;
+            Ok(true)
+        })
+        .unwrap();
+    assert_eq!(
+        seen,
+        vec![
+            (
+                vec![3],
+                (false, false, false),
+                false,
+                0.0,
+                0.0,
+                (false, false)
+            ),
+            (
+                vec![3],
+                (true, false, false),
+                true,
+                0.0,
+                0.0,
+                (false, false)
+            ),
+            (
+                vec![3],
+                (false, true, false),
+                false,
+                -1.0,
+                -1.0,
+                (false, false)
+            ),
+            (
+                vec![3],
+                (false, false, true),
+                false,
+                1.0,
+                1.0,
+                (true, false)
+            ),
+            (
+                vec![3],
+                (false, false, false),
+                false,
+                1.0,
+                1.0,
+                (false, true)
+            ),
+            (
+                vec![],
+                (false, false, false),
+                false,
+                0.0,
+                0.0,
+                (false, false)
+            ),
+        ]
+    );
+}
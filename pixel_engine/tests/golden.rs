@@ -0,0 +1,313 @@
+extern crate pixel_engine as px;
+
+use px::decals::DecalText;
+use px::testing::{assert_golden, run_frames, InputScript};
+use px::traits::*;
+use px::transform::{Sampling, Transform2D};
+use px::{Color, PixelMode, Sprite};
+
+macro_rules! golden {
+    ($name:literal) => {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/", $name, ".png")
+    };
+}
+
+fn draw_once<F: FnMut(&mut px::Engine) + 'static>(size: (u32, u32), mut f: F) -> Sprite {
+    run_frames(size, 1, &InputScript::new(), move |engine| {
+        engine.clear(Color::BLACK);
+        f(engine);
+        Ok(true)
+    })
+    .unwrap()
+}
+
+#[test]
+fn draw_line() {
+    let frame = draw_once((32, 32), |engine| {
+        engine.draw_line((0, 0), (31, 31), Color::WHITE);
+        engine.draw_line((31, 0), (0, 31), Color::RED);
+        engine.draw_line((16, 2), (16, 29), Color::GREEN);
+        engine.draw_line((2, 16), (29, 16), Color::BLUE);
+        engine.draw_line((3, 25), (28, 20), Color::YELLOW);
+        engine.draw_line((25, 3), (20, 28), Color::CYAN);
+    });
+    assert_golden(&frame, golden!("draw_line"), 0);
+}
+
+#[test]
+fn fill_triangle() {
+    let frame = draw_once((32, 32), |engine| {
+        engine.fill_triangle((2, 2), (29, 8), (10, 29), Color::MAGENTA);
+        engine.fill_triangle((30, 30), (20, 28), (28, 15), Color::GREEN);
+    });
+    assert_golden(&frame, golden!("fill_triangle"), 0);
+}
+
+#[test]
+fn draw_text() {
+    let frame = draw_once((64, 32), |engine| {
+        engine.draw_text((0, 0), 1, Color::WHITE, "Hello,\nWorld!");
+        engine.draw_text((0, 16), 2, Color::RED, "px");
+    });
+    assert_golden(&frame, golden!("draw_text"), 0);
+}
+
+#[test]
+fn draw_partial_sprite() {
+    let mut sprite = Sprite::new(8, 8);
+    for y in 0..8 {
+        for x in 0..8 {
+            sprite.set_pixel(x, y, Color::new(x as u8 * 32, y as u8 * 32, 128));
+        }
+    }
+    let frame = draw_once((32, 32), move |engine| {
+        engine.draw_partial_sprite((0, 0), &sprite, (2, 2), (4, 4), 1, (false, false));
+        engine.draw_partial_sprite((8, 0), &sprite, (2, 2), (4, 4), 1, (true, false));
+        engine.draw_partial_sprite((16, 0), &sprite, (2, 2), (4, 4), 1, (false, true));
+        engine.draw_partial_sprite((0, 8), &sprite, (0, 0), (6, 3), 2, (false, false));
+    });
+    assert_golden(&frame, golden!("draw_partial_sprite"), 0);
+}
+
+#[test]
+fn clip_and_transform() {
+    let frame = draw_once((32, 32), |engine| {
+        // A panel drawn in local coordinates, the circle overflows it
+        engine.push_transform((4, 4), 1.0);
+        engine.push_clip((0, 0), (12, 12));
+        engine.fill_rect((0, 0), (12, 12), Color::DARK_BLUE);
+        engine.fill_circle((10, 10), 6, Color::RED);
+        engine.pop_clip();
+        engine.pop_transform();
+        // A minimap twice smaller, then a nested view twice bigger
+        engine.push_transform((18, 2), 0.5);
+        engine.draw_rect((0, 0), (24, 24), Color::GREEN);
+        engine.draw_text((4, 4), 1, Color::WHITE, "A");
+        engine.push_transform((0, 32), 2.0);
+        engine.push_clip((0, 0), (6, 6));
+        engine.fill_triangle((0, 0), (10, 0), (0, 10), Color::YELLOW);
+        engine.pop_clip();
+        engine.pop_transform();
+        engine.pop_transform();
+        // Nothing is left on the stacks
+        engine.draw((31, 31), Color::WHITE);
+    });
+    assert_golden(&frame, golden!("clip_and_transform"), 0);
+}
+
+#[test]
+fn alpha_shrunk_by_a_transform() {
+    let frame = draw_once((16, 16), |engine| {
+        engine.set_pixel_mode(PixelMode::Alpha);
+        // Four source pixels land on each target pixel, it must be blended only once
+        engine.push_transform((2, 2), 0.5);
+        engine.fill_rect((0, 0), (16, 16), Color::new_with_alpha(255, 0, 0, 128));
+        engine.draw_line((0, 20), (23, 20), Color::new_with_alpha(255, 255, 255, 128));
+        engine.pop_transform();
+    });
+    assert_eq!(frame.get_pixel(2, 2), frame.get_pixel(9, 9));
+    assert_eq!(frame.get_pixel(2, 2), Color::new(128, 0, 0));
+    assert_golden(&frame, golden!("alpha_shrunk_by_a_transform"), 0);
+}
+
+#[test]
+fn layers() {
+    let mut screenshot = None;
+    let frame = run_frames((32, 32), 1, &InputScript::new(), |engine| {
+        let below = engine.create_layer();
+        let above = engine.create_layer();
+        let hidden = engine.create_layer();
+        let stretched = engine.create_layer();
+        engine.clear(Color::DARK_BLUE);
+        engine.draw_line((0, 31), (31, 0), Color::WHITE);
+        // The decal is attached to the layer below, so the layer above covers it
+        engine.set_draw_target(below);
+        engine.fill_rect((4, 4), (12, 12), Color::RED);
+        engine.draw_text_decal((6.0, 6.0), "#", (1.0, 1.0), Color::WHITE);
+        engine.set_draw_target(above);
+        engine.fill_rect((0, 0), (8, 8), Color::GREEN);
+        engine.set_layer_offset(above, (8.0, 8.0));
+        engine.set_layer_tint(above, Color::new_with_alpha(255, 255, 255, 128));
+        engine.set_draw_target(hidden);
+        engine.clear(Color::YELLOW);
+        engine.draw_text_decal((0.0, 0.0), "#", (1.0, 1.0), Color::YELLOW);
+        engine.set_layer_enabled(hidden, false);
+        engine.set_draw_target(stretched);
+        engine.fill_rect((12, 0), (4, 2), Color::YELLOW);
+        engine.set_layer_scale(stretched, (2.0, 2.0));
+        engine.set_draw_target(px::Layer::SCREEN);
+        engine.draw((31, 31), Color::WHITE);
+        screenshot = Some(engine.screenshot());
+        Ok(true)
+    })
+    .unwrap();
+    px::testing::compare(&screenshot.unwrap(), &frame, 0).unwrap();
+    assert_golden(&frame, golden!("layers"), 0);
+}
+
+#[test]
+fn draw_on_sprites() {
+    // A tile baked once, with a translucent highlight
+    let mut tile = Sprite::new_with_color(8, 8, Color::BLANK);
+    tile.draw_with(|target| {
+        target.fill_rect((0, 0), (8, 8), Color::DARK_BLUE);
+        target.draw_rect((0, 0), (7, 7), Color::WHITE);
+        target.set_pixel_mode(PixelMode::Alpha);
+        target.fill_rect((1, 1), (3, 3), Color::new_with_alpha(255, 255, 0, 128));
+    });
+    // Each quarter of an atlas is drawn on through its own subsprite
+    let mut atlas = Sprite::new_with_color(16, 16, Color::BLACK);
+    let atlas_ref = &atlas;
+    let mut quarters: Vec<_> = [(0, 0), (8, 0), (0, 8), (8, 8)]
+        .iter()
+        .map(|&pos| atlas_ref.create_sub_sprite(pos.into(), (8, 8).into()).unwrap())
+        .collect();
+    for (i, quarter) in quarters.iter_mut().enumerate() {
+        quarter.draw_with(|target| {
+            target.draw_sprite((0, 0), 1, &tile, (i % 2 == 1, i >= 2));
+            // Positions are relative to the subsprite, and clipped to it
+            target.set_pixel_mode(PixelMode::Xor);
+            target.fill_rect((6, 6), (4, 4), Color::GREEN);
+        });
+    }
+    drop(quarters);
+    atlas.draw_with(|target| target.draw_line((0, 15), (15, 0), Color::RED));
+    let frame = draw_once((32, 32), move |engine| {
+        engine.draw_sprite((0, 0), 1, &tile, (false, false));
+        engine.draw_sprite((0, 16), 1, &atlas, (false, false));
+        engine.draw_sprite((16, 16), 1, &atlas, (false, false));
+    });
+    assert_golden(&frame, golden!("draw_on_sprites"), 0);
+}
+
+#[test]
+fn draw_sprite_transformed() {
+    let mut arrow = Sprite::new_with_color(8, 8, Color::BLANK);
+    arrow.draw_with(|target| {
+        target.fill_rect((0, 3), (6, 2), Color::WHITE);
+        target.fill_triangle((4, 0), (7, 3), (4, 7), Color::RED);
+    });
+    let spin = Transform2D::new()
+        .translate(-4.0, -4.0)
+        .rotate(std::f32::consts::FRAC_PI_4)
+        .translate(8.0, 8.0);
+    let back = spin.then(&spin.invert().unwrap()).forward((3.0, 5.0));
+    assert!((back.x - 3.0).abs() < 1e-4 && (back.y - 5.0).abs() < 1e-4);
+    assert_eq!(Transform2D::new().scale(0.0, 1.0).invert(), None);
+
+    let frame = draw_once((32, 32), move |engine| {
+        engine.draw_sprite_transformed(&arrow, &spin, Sampling::Nearest);
+        let stretched = Transform2D::new().scale(1.5, 1.5).translate(16.0, 2.0);
+        engine.draw_sprite_transformed(&arrow, &stretched, Sampling::Bilinear);
+        let sheared = Transform2D::new().shear(0.5, 0.0).translate(2.0, 18.0);
+        engine.draw_sprite_transformed(&arrow, &sheared, Sampling::Nearest);
+        // The pixel mode applies, the transparent pixels are skipped
+        engine.fill_rect((16, 16), (16, 16), Color::DARK_BLUE);
+        engine.set_pixel_mode(PixelMode::Alpha);
+        let flipped = Transform2D::new().scale(-1.0, 1.0).translate(28.0, 20.0);
+        engine.draw_sprite_transformed(&arrow, &flipped, Sampling::Nearest);
+        engine.set_pixel_mode(PixelMode::Normal);
+    });
+    assert_golden(&frame, golden!("draw_sprite_transformed"), 0);
+}
+
+#[test]
+fn draw_sprite_transformed_clipped() {
+    let mut checker = Sprite::new(2, 2);
+    checker.draw_with(|target| {
+        target.draw((0, 0), Color::RED);
+        target.draw((1, 1), Color::RED);
+    });
+    let empty = Sprite::new(0, 0);
+    assert_eq!(
+        Sampling::Bilinear.sample(&empty, (0.5, 0.5).into()),
+        Color::BLANK
+    );
+    let frame = draw_once((16, 16), move |engine| {
+        // Only the few pixels in the clip are sampled out of the millions covered
+        let huge = Transform2D::new().scale(1e4, 1e4).translate(-9998.0, -9998.0);
+        engine.push_transform((4, 4), 2.0);
+        engine.push_clip((0, 0), (4, 4));
+        engine.draw_sprite_transformed(&checker, &huge, Sampling::Nearest);
+        engine.draw_sprite_transformed(&empty, &Transform2D::IDENTITY, Sampling::Bilinear);
+        engine.pop_clip();
+        engine.pop_transform();
+    });
+    assert_golden(&frame, golden!("draw_sprite_transformed_clipped"), 0);
+}
+
+#[test]
+fn decals() {
+    // A quarter of the sprite is translucent, its pixels are blended over the screen
+    let mut sprite = Sprite::new(8, 8);
+    for y in 0..8 {
+        for x in 0..8 {
+            let col = match (x < 4, y < 4) {
+                (true, true) => Color::RED,
+                (false, true) => Color::GREEN,
+                (true, false) => Color::YELLOW,
+                (false, false) => Color::new_with_alpha(255, 255, 255, 128),
+            };
+            sprite.set_pixel(x, y, col);
+        }
+    }
+    let mut screenshot = None;
+    run_frames((48, 48), 1, &InputScript::new(), |engine| {
+        engine.clear(Color::BLACK);
+        let decal = engine.create_decal(&sprite);
+        engine.fill_rect((0, 0), (48, 4), Color::DARK_BLUE);
+        engine.draw_decal((0.0, 0.0), &decal);
+        engine.draw_decal_tinted((12.0, 0.0), &decal, Color::new_with_alpha(0, 0, 255, 128));
+        engine.draw_partial_decal((24.0, 0.0), &decal, (2.0, 2.0), (4.0, 4.0));
+        engine.draw_rotated_decal(
+            (40.0, 8.0),
+            &decal,
+            std::f32::consts::FRAC_PI_4,
+            (4.0, 4.0),
+        );
+        // Perspective-correct texture mapping on a trapezoid
+        engine.draw_warped_decal(
+            [(16.0, 20.0), (2.0, 46.0), (46.0, 46.0), (32.0, 20.0)],
+            &decal,
+        );
+        // The texture is gone once destroyed, so the frame is captured before
+        screenshot = Some(engine.screenshot());
+        engine.destroy_decal(&decal);
+        Ok(true)
+    })
+    .unwrap();
+    assert_golden(&screenshot.unwrap(), golden!("decals"), 0);
+}
+
+#[test]
+fn decal_alpha_blending() {
+    // Translucent white and red over black and white. The decals are blended in linear space,
+    // like the GPU does on its sRGB target, so half-transparent white over black is lighter
+    // than the average of the bytes
+    let mut sprite = Sprite::new(16, 4);
+    for y in 0..4 {
+        for x in 0..16 {
+            let alpha = x as u8 * 17;
+            let col = if y % 2 == 0 {
+                Color::new_with_alpha(255, 255, 255, alpha)
+            } else {
+                Color::new_with_alpha(255, 0, 0, alpha)
+            };
+            sprite.set_pixel(x, y, col);
+        }
+    }
+    let mut screenshot = None;
+    run_frames((16, 4), 1, &InputScript::new(), |engine| {
+        engine.clear(Color::BLACK);
+        engine.fill_rect((0, 2), (16, 2), Color::WHITE);
+        let decal = engine.create_decal(&sprite);
+        engine.draw_decal((0.0, 0.0), &decal);
+        screenshot = Some(engine.screenshot());
+        engine.destroy_decal(&decal);
+        Ok(true)
+    })
+    .unwrap();
+    let screenshot = screenshot.unwrap();
+    assert_eq!(screenshot.get_pixel(8, 0), Color::new(193, 193, 193));
+    assert_golden(&screenshot, golden!("decal_alpha_blending"), 0);
+}
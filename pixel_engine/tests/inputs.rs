@@ -0,0 +1,200 @@
+extern crate pixel_engine as px;
+
+mod common;
+
+use px::actions::Binding;
+use px::inputs::{CursorGrab, InputEvent, Keycodes, Modifiers, MouseBtn, MouseWheel};
+use px::testing::InputScript;
+
+#[test]
+fn scripted_inputs() {
+    let script = InputScript::new()
+        .key_tap(1, 2, Keycodes::Space)
+        .at(2, InputEvent::MouseMove(5.0, 6.0))
+        .at(2, InputEvent::MousePress(MouseBtn::Left));
+    let seen = common::observe(5, &script, |engine| {
+        let space = engine.get_key(Keycodes::Space);
+        let mouse = engine.get_mouse_btn(MouseBtn::Left);
+        (
+            space.pressed,
+            space.held,
+            space.released,
+            mouse.pressed,
+            engine.get_mouse_location(),
+        )
+    });
+    assert_eq!(
+        seen,
+        vec![
+            (false, false, false, false, (0, 0)),
+            (true, false, false, false, (0, 0)),
+            (false, true, false, true, (5, 6)),
+            (false, false, true, false, (5, 6)),
+            (false, false, false, false, (5, 6)),
+        ]
+    );
+}
+
+#[test]
+fn text_input() {
+    let script = InputScript::new()
+        .at(1, InputEvent::Character('A'))
+        .at(1, InputEvent::Character('é'))
+        .at(3, InputEvent::Character('€'))
+        .at(3, InputEvent::Character('ß'));
+    let seen = common::observe(5, &script, |engine| engine.get_text_input().to_owned());
+    assert_eq!(seen, vec!["", "Aé", "", "€ß", ""]);
+}
+
+#[test]
+fn modifiers_chords_and_repeats() {
+    let script = InputScript::new()
+        .at(1, InputEvent::KeyPress(Keycodes::LControl))
+        .key_tap(2, 3, Keycodes::S)
+        .at(3, InputEvent::KeyPress(Keycodes::S))
+        .at(4, InputEvent::Modifiers(Modifiers::SHIFT))
+        .at(5, InputEvent::KeyRelease(Keycodes::LControl))
+        .at(6, InputEvent::KeyPress(Keycodes::S));
+    let seen = common::observe(7, &script, |engine| {
+        let s = engine.get_key(Keycodes::S);
+        let chord = engine.get_chord(Modifiers::CTRL, Keycodes::S);
+        (
+            engine.get_modifiers(),
+            s.pressed,
+            s.repeated,
+            chord.pressed,
+            chord.any(),
+        )
+    });
+    let ctrl = Modifiers::CTRL;
+    let ctrl_shift = Modifiers::CTRL | Modifiers::SHIFT;
+    assert_eq!(
+        seen,
+        vec![
+            (Modifiers::empty(), false, false, false, false),
+            (ctrl, false, false, false, false),
+            (ctrl, true, false, true, true),
+            (ctrl, false, true, false, true),
+            (ctrl_shift, false, false, false, false),
+            (Modifiers::SHIFT, false, false, false, false),
+            (Modifiers::SHIFT, true, false, false, false),
+        ]
+    );
+}
+
+#[test]
+fn mouse_motion_scroll_and_buttons() {
+    let script = InputScript::new()
+        .at(1, InputEvent::MouseMove(1.5, 2.25))
+        .at(2, InputEvent::MouseMove(2.0, 2.0))
+        .at(2, InputEvent::MouseMove(3.0, 2.25))
+        .at(2, InputEvent::MouseMotion(4.0, -1.0))
+        .at(2, InputEvent::MouseMotion(2.0, 0.5))
+        .at(2, InputEvent::MouseScroll(0.5, -2.0))
+        .at(2, InputEvent::MouseWheel(MouseWheel::Up))
+        .at(2, InputEvent::MousePress(MouseBtn::Back))
+        .at(2, InputEvent::MousePress(MouseBtn::Other(12)))
+        .at(3, InputEvent::MouseMove(8.0, 8.0))
+        .at(3, InputEvent::MouseRelease(MouseBtn::Back))
+        .at(4, InputEvent::MouseLeft)
+        .at(4, InputEvent::MouseScroll(3.0, 1.0));
+    let seen = common::observe(5, &script, |engine| {
+        assert_eq!(engine.set_cursor_grab(CursorGrab::Locked), Ok(()));
+        let back = engine.get_mouse_btn(MouseBtn::Back);
+        (
+            engine.get_mouse_position(),
+            engine.get_mouse_location(),
+            engine.get_mouse_delta(),
+            engine.get_mouse_raw_delta(),
+            engine.get_mouse_scroll(),
+            engine.get_mouse_wheel(),
+            (back.pressed, back.held, back.released),
+            engine.get_mouse_btn(MouseBtn::Other(12)).pressed,
+            engine.is_mouse_in_window(),
+            engine.get_pressed_binding(),
+        )
+    });
+    let none = (false, false, false);
+    assert_eq!(
+        seen,
+        vec![
+            (
+                (0.0, 0.0),
+                (0, 0),
+                (0.0, 0.0),
+                (0.0, 0.0),
+                (0.0, 0.0),
+                MouseWheel::None,
+                none,
+                false,
+                false,
+                None
+            ),
+            (
+                (1.5, 2.25),
+                (1, 2),
+                (0.0, 0.0),
+                (0.0, 0.0),
+                (0.0, 0.0),
+                MouseWheel::None,
+                none,
+                false,
+                true,
+                None
+            ),
+            (
+                (3.0, 2.25),
+                (3, 2),
+                (1.5, 0.0),
+                (6.0, -0.5),
+                (0.5, -3.0),
+                MouseWheel::Up,
+                (true, false, false),
+                true,
+                true,
+                Some(Binding::Wheel(MouseWheel::Up))
+            ),
+            (
+                (8.0, 8.0),
+                (7, 7),
+                (5.0, 5.75),
+                (0.0, 0.0),
+                (0.0, 0.0),
+                MouseWheel::None,
+                (false, false, true),
+                false,
+                true,
+                None
+            ),
+            (
+                (8.0, 8.0),
+                (7, 7),
+                (0.0, 0.0),
+                (0.0, 0.0),
+                (3.0, 1.0),
+                MouseWheel::Right,
+                none,
+                false,
+                false,
+                Some(Binding::Wheel(MouseWheel::Right))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn mouse_delta_starts_in_the_window() {
+    let script = InputScript::new()
+        .at(0, InputEvent::MouseMove(3.0, 3.0))
+        .at(1, InputEvent::MouseMove(4.0, 5.0))
+        .at(2, InputEvent::MouseLeft)
+        .at(3, InputEvent::MouseEntered)
+        .at(3, InputEvent::MouseMove(1.0, 1.0))
+        .at(4, InputEvent::MouseMove(2.0, 1.0));
+    let seen = common::observe(5, &script, |engine| engine.get_mouse_delta());
+    // Neither the first position nor the one after coming back are a movement
+    assert_eq!(
+        seen,
+        vec![(0.0, 0.0), (1.0, 2.0), (0.0, 0.0), (0.0, 0.0), (1.0, 0.0)]
+    );
+}
@@ -0,0 +1,37 @@
+extern crate pixel_engine as px;
+
+use px::testing::{run_frames, InputScript};
+use px::traits::*;
+use px::{Color, Layer, PixelMode};
+
+#[test]
+fn draw_state_follows_the_target() {
+    let frame = run_frames((8, 8), 1, &InputScript::new(), |engine| {
+        let above = engine.create_layer();
+        let other = engine.create_layer();
+        engine.clear(Color::BLACK);
+        engine.set_pixel_mode(PixelMode::Alpha);
+        engine.set_blend_factor(0.5);
+        engine.push_transform((2, 2), 1.0);
+        engine.push_clip((0, 0), (2, 2));
+        for layer in [above, other, Layer::SCREEN, above] {
+            engine.set_draw_target(layer);
+            assert_eq!(engine.get_pixel_mode(), PixelMode::Alpha);
+            assert!((engine.get_blend_factor() - 0.5).abs() < f32::EPSILON);
+        }
+        // Moved by the transform and cut by the clip, blended with the blend factor
+        engine.fill_rect((0, 0), (4, 4), Color::WHITE);
+        engine.pop_clip();
+        engine.pop_transform();
+        engine.set_draw_target(Layer::SCREEN);
+        engine.set_pixel_mode(PixelMode::Normal);
+        Ok(true)
+    })
+    .unwrap();
+    let on_layer = frame.get_pixel(2, 2);
+    assert!(on_layer.r > 100 && on_layer.r < 155, "{:?}", on_layer);
+    assert_eq!(frame.get_pixel(3, 3), on_layer);
+    for (x, y) in [(1, 1), (4, 4), (2, 4), (4, 2)] {
+        assert_eq!(frame.get_pixel(x, y), Color::BLACK);
+    }
+}
@@ -0,0 +1,95 @@
+extern crate pixel_engine as px;
+
+use px::events::Event;
+use px::inputs::{InputEvent, Keycodes};
+
+/// Logs every call, stops after `frames` updates (with an error if `fail` is set)
+struct Lifecycle {
+    log: std::rc::Rc<std::cell::RefCell<Vec<String>>>,
+    frames: usize,
+    fail: bool,
+}
+
+impl px::Game for Lifecycle {
+    fn on_user_create(
+        &mut self,
+        _engine: &mut px::Engine,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        self.log.borrow_mut().push("create".to_owned());
+        Ok(self.frames > 0)
+    }
+
+    fn on_user_update(
+        &mut self,
+        _engine: &mut px::Engine,
+        elapsed: f64,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        assert_eq!(elapsed, px::HEADLESS_ELAPSED);
+        self.log.borrow_mut().push("update".to_owned());
+        self.frames -= 1;
+        match (self.frames, self.fail) {
+            (0, true) => Err("failed".into()),
+            (0, false) => Ok(false),
+            _ => Ok(true),
+        }
+    }
+
+    fn on_user_destroy(&mut self, _engine: &mut px::Engine) {
+        self.log.borrow_mut().push("destroy".to_owned());
+    }
+
+    fn on_event(&mut self, _engine: &mut px::Engine, event: &Event) {
+        self.log.borrow_mut().push(format!("{event:?}"));
+    }
+}
+
+#[test]
+fn game_lifecycle() {
+    fn run(frames: usize, fail: bool) -> (Vec<String>, bool) {
+        // The game is moved into the wrapper, so the log is shared
+        let log = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let game = Lifecycle {
+            log: log.clone(),
+            frames,
+            fail,
+        };
+        let mut wrapper = px::EngineWrapper::new_headless((4, 4));
+        let mut trace = px::trace::InputTrace::new((4, 4));
+        for inputs in [
+            vec![],
+            vec![
+                InputEvent::KeyPress(Keycodes::A),
+                InputEvent::MouseMove(1.0, 2.0),
+            ],
+        ] {
+            trace.frames.push(px::trace::FrameInputs {
+                elapsed: px::HEADLESS_ELAPSED,
+                inputs,
+            });
+        }
+        wrapper.start_replay(trace);
+        let result = wrapper.run_app_headless(10, game);
+        let log = log.borrow().clone();
+        (log, result.is_ok())
+    }
+    let (log, ok) = run(3, false);
+    assert!(ok);
+    assert_eq!(
+        log,
+        [
+            "create",
+            "update",
+            "Input(KeyPress(A))",
+            "Input(MouseMove(1.0, 2.0))",
+            "update",
+            "update",
+            "destroy"
+        ]
+    );
+    let (log, ok) = run(1, true);
+    assert!(!ok);
+    assert_eq!(log, ["create", "update", "destroy"]);
+    let (log, ok) = run(0, false);
+    assert!(ok);
+    assert_eq!(log, ["create", "destroy"]);
+}
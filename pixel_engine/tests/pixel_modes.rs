@@ -0,0 +1,64 @@
+extern crate pixel_engine as px;
+
+use px::testing::{run_frames, InputScript};
+use px::traits::*;
+use px::{Color, PixelMode};
+
+#[test]
+fn pixel_modes() {
+    fn swap_channels(_x: u32, _y: u32, src: Color, dst: Color) -> Color {
+        Color::new(dst.b, src.g, dst.r)
+    }
+    let dst = Color::new(100, 150, 200);
+    let src = Color::new_with_alpha(200, 100, 50, 255);
+    let half = Color::new_with_alpha(200, 100, 50, 128);
+    let modes = [
+        (PixelMode::Normal, src),
+        (PixelMode::Mask, half),
+        (PixelMode::Alpha, half),
+        (PixelMode::Additive, src),
+        (PixelMode::Additive, half),
+        (PixelMode::Subtract, src),
+        (PixelMode::Multiply, src),
+        (PixelMode::Screen, src),
+        (PixelMode::Min, src),
+        (PixelMode::Max, src),
+        (PixelMode::Xor, src),
+        (PixelMode::Custom(swap_channels), src),
+    ];
+    let frame = run_frames(
+        (modes.len() as u32, 2),
+        1,
+        &InputScript::new(),
+        move |engine| {
+            engine.clear(dst);
+            for (x, (mode, col)) in modes.iter().enumerate() {
+                engine.set_pixel_mode(*mode);
+                engine.draw((x as i32, 0), *col);
+                engine.fill_rect((x as i32, 1), (1, 1), *col);
+            }
+            engine.set_pixel_mode(PixelMode::Normal);
+            Ok(true)
+        },
+    )
+    .unwrap();
+    let expected = [
+        Color::new(200, 100, 50),
+        dst,
+        Color::new(150, 124, 124),
+        Color::new(255, 250, 250),
+        Color::new(200, 200, 225),
+        Color::new(0, 50, 150),
+        Color::new(78, 58, 39),
+        Color::new(221, 191, 210),
+        Color::new(100, 100, 50),
+        Color::new(200, 150, 200),
+        Color::new(100 ^ 200, 150 ^ 100, 200 ^ 50),
+        Color::new(200, 100, 100),
+    ];
+    for (x, expected) in expected.iter().enumerate() {
+        assert_eq!(frame.get_pixel(x as u32, 0), *expected, "{:?}", modes[x].0);
+        // The shapes go through the same path
+        assert_eq!(frame.get_pixel(x as u32, 1), *expected, "{:?}", modes[x].0);
+    }
+}
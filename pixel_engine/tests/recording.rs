@@ -0,0 +1,169 @@
+extern crate pixel_engine as px;
+
+use px::decals::DecalText;
+use px::inputs::{InputEvent, Keycodes, MouseBtn};
+use px::recorder::RecordingFormat;
+use px::testing::{run_frames, InputScript};
+use px::traits::*;
+use px::{Color, Sprite};
+
+#[test]
+fn screenshot_matches_presented_frame() {
+    let mut screenshot = None;
+    let frame = run_frames((32, 32), 1, &InputScript::new(), |engine| {
+        engine.clear(Color::BLACK);
+        engine.draw_line((0, 0), (31, 31), Color::WHITE);
+        engine.draw_text_decal((8.0, 4.0), "#", (1.0, 1.0), Color::RED);
+        screenshot = Some(engine.screenshot());
+        Ok(true)
+    })
+    .unwrap();
+    let screenshot = screenshot.unwrap();
+    let has_decal = (0..32).any(|y| (0..32).any(|x| screenshot.get_pixel(x, y) == Color::RED));
+    assert!(has_decal);
+    px::testing::compare(&screenshot, &frame, 0).unwrap();
+    let decoded = Sprite::load_image_bytes(&screenshot.encode_png().unwrap()).unwrap();
+    px::testing::compare(&decoded, &frame, 0).unwrap();
+}
+
+#[test]
+fn screenshot_key() {
+    let dir = std::env::temp_dir().join(format!("px_screenshots_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let script = InputScript::new()
+        .key_tap(0, 1, Keycodes::F12)
+        .key_tap(2, 1, Keycodes::F12);
+    let mut bound = false;
+    run_frames((8, 8), 4, &script, |engine| {
+        if !bound {
+            engine.set_screenshot_key(Some(Keycodes::F12), &dir);
+            bound = true;
+        }
+        engine.clear(Color::BLUE);
+        Ok(true)
+    })
+    .unwrap();
+    let first = Sprite::load_from_file(dir.join("screenshot_0000.png")).unwrap();
+    assert_eq!(first.get_pixel(3, 3), Color::BLUE);
+    assert!(dir.join("screenshot_0001.png").exists());
+    assert!(!dir.join("screenshot_0002.png").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Frame `i` is cleared with a different color, so the recorded frames can be told apart
+fn record_frames(format: RecordingFormat, path: &std::path::Path, fps: u32, frames: usize) {
+    let mut frame = 0;
+    run_frames((4, 4), frames, &InputScript::new(), |engine| {
+        if frame == 0 {
+            engine.start_recording(format, path, fps)?;
+        }
+        engine.clear(Color::new(frame * 10, 0, 0));
+        frame += 1;
+        Ok(true)
+    })
+    .unwrap();
+}
+
+#[test]
+fn record_png_sequence() {
+    let dir = std::env::temp_dir().join(format!("px_recording_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    // The headless engine runs at 60 fps, so one frame every two is kept: the first one
+    // presented in each tick
+    record_frames(RecordingFormat::PngSequence, &dir, 30, 6);
+    let reds: Vec<u8> = (0..3)
+        .map(|i| {
+            let frame = Sprite::load_from_file(dir.join(format!("frame_{i:05}.png"))).unwrap();
+            frame.get_pixel(0, 0).r
+        })
+        .collect();
+    assert_eq!(reds, vec![0, 20, 40]);
+    assert!(!dir.join("frame_00003.png").exists());
+    // A higher frame rate than the game: frames are repeated
+    std::fs::remove_dir_all(&dir).unwrap();
+    record_frames(RecordingFormat::PngSequence, &dir, 120, 3);
+    assert!(dir.join("frame_00004.png").exists());
+    assert!(!dir.join("frame_00005.png").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn record_gif() {
+    use image::AnimationDecoder;
+    let path = std::env::temp_dir().join(format!("px_recording_{}.gif", std::process::id()));
+    record_frames(RecordingFormat::Gif, &path, 20, 12);
+    let file = std::io::BufReader::new(std::fs::File::open(&path).unwrap());
+    let frames = image::codecs::gif::GifDecoder::new(file)
+        .unwrap()
+        .into_frames()
+        .collect_frames()
+        .unwrap();
+    // 12 frames at 60 fps last 0.2s: 4 ticks at 20 fps, each one lasting 5 hundredths
+    assert_eq!(frames.len(), 4);
+    for frame in &frames {
+        assert_eq!(frame.delay().numer_denom_ms(), (50, 1));
+    }
+    assert_eq!(frames[1].buffer().get_pixel(0, 0)[0], 30);
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// A tiny game depending on its inputs and on `elapsed`
+fn moving_dot(pos: &mut (f64, f64), engine: &mut px::Engine) -> (bool, bool, (u32, u32)) {
+    if engine.get_key(Keycodes::Right).held {
+        pos.0 += 60.0 * engine.elapsed;
+    }
+    if engine.get_mouse_btn(MouseBtn::Left).pressed {
+        let (x, y) = engine.get_mouse_location();
+        *pos = (f64::from(x), f64::from(y));
+    }
+    engine.clear(Color::BLACK);
+    #[allow(clippy::cast_possible_truncation)]
+    engine.draw((pos.0 as i32, pos.1 as i32), Color::WHITE);
+    let right = engine.get_key(Keycodes::Right);
+    (right.pressed, right.held, engine.get_mouse_location())
+}
+
+#[test]
+fn record_and_replay_inputs() {
+    let script = InputScript::new()
+        .key_tap(1, 4, Keycodes::Right)
+        .at(3, InputEvent::MouseMove(2.0, 9.0))
+        .at(6, InputEvent::MousePress(MouseBtn::Left));
+    let mut recorded = Vec::new();
+    let mut trace = None;
+    let mut pos = (0.0, 0.0);
+    let mut frame = 0;
+    let recorded_frame = run_frames((16, 16), 9, &script, |engine| {
+        if frame == 0 {
+            engine.start_input_recording();
+        }
+        frame += 1;
+        recorded.push(moving_dot(&mut pos, engine));
+        if frame == 9 {
+            trace = engine.stop_input_recording();
+        }
+        Ok(true)
+    })
+    .unwrap();
+    // The last frame is not over when the recording is stopped
+    let trace = trace.unwrap();
+    assert_eq!(trace.frames.len(), 8);
+
+    let path = std::env::temp_dir().join(format!("px_trace_{}.ron", std::process::id()));
+    trace.save_to_file(&path).unwrap();
+    let trace = px::trace::InputTrace::load_from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut replayed = Vec::new();
+    let mut pos = (0.0, 0.0);
+    let replayed_frame = px::testing::replay(&trace, |engine| {
+        replayed.push(moving_dot(&mut pos, engine));
+        Ok(true)
+    })
+    .unwrap();
+    assert_eq!(replayed, recorded[..8]);
+    assert_eq!(replayed.last(), Some(&(false, false, (2, 9))));
+    // Nothing moves during the last frames, so the last frame of the replay matches the one of the
+    // recording
+    px::testing::compare(&replayed_frame, &recorded_frame, 0).unwrap();
+}
@@ -0,0 +1,23 @@
+extern crate pixel_engine as px;
+
+use px::{Color, Sprite};
+
+#[test]
+fn failure_files_are_removed_once_it_passes() {
+    let dir = std::env::temp_dir().join(format!("px_golden_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let reference = dir.join("square.png");
+    let black = Sprite::new_with_color(2, 2, Color::BLACK);
+    let white = Sprite::new_with_color(2, 2, Color::WHITE);
+    assert!(px::testing::check_golden(&black, &reference, 0).is_err());
+    assert!(px::testing::check_golden(&white, &reference, 0).is_err());
+    let artifacts = [
+        "square.actual.png",
+        "square.expected.png",
+        "square.diff.png",
+    ];
+    assert!(artifacts.iter().all(|name| dir.join(name).exists()));
+    px::testing::check_golden(&black, &reference, 0).unwrap();
+    assert!(artifacts.iter().all(|name| !dir.join(name).exists()));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
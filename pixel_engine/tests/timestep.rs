@@ -0,0 +1,46 @@
+extern crate pixel_engine as px;
+
+#[test]
+fn fixed_timestep() {
+    use px::timestep::FixedTimestep;
+    use px::trace::{FrameInputs, InputTrace};
+    // Replaying a trace is an easy way to choose the elapsed of every frame
+    let mut trace = InputTrace::new((4, 4));
+    for elapsed in [3.0 / 128.0, 1.0 / 128.0, 1.0, 1.0 / 256.0] {
+        trace.frames.push(FrameInputs {
+            elapsed,
+            inputs: Vec::new(),
+        });
+    }
+    let mut timestep = FixedTimestep::new(64)
+        .max_updates_per_frame(4)
+        .max_frame_time(0.125);
+    let updates = std::cell::Cell::new(0);
+    let mut frames = Vec::new();
+    px::testing::replay(&trace, |engine| {
+        timestep.frame(
+            engine,
+            &mut |engine, dt| {
+                assert_eq!(dt, 1.0 / 64.0);
+                assert_eq!(engine.elapsed, dt);
+                updates.set(updates.get() + 1);
+                Ok(true)
+            },
+            &mut |engine, alpha| {
+                frames.push((updates.take(), alpha, engine.elapsed));
+                Ok(true)
+            },
+        )
+    })
+    .unwrap();
+    assert_eq!(
+        frames,
+        vec![
+            (1, 0.5, 3.0 / 128.0),
+            (1, 0.0, 1.0 / 128.0),
+            // Clamped to 8 ticks, only 4 are run and the others are dropped
+            (4, 0.0, 1.0),
+            (0, 0.25, 1.0 / 256.0),
+        ]
+    );
+}
@@ -3,6 +3,7 @@ use wgpu::util::DeviceExt;
 pub type DecalTextureID = usize;
 
 mod gpu_vector;
+pub mod software;
 
 #[derive(Debug)]
 pub struct DecalInstances {
@@ -11,6 +12,8 @@ pub struct DecalInstances {
     pub uv: [(f32, f32); 4],
     pub w: [f32; 4],
     pub tint: [f32; 4],
+    /// The layer the instance is drawn over, `0` is the screen and `n` the `n`th [`crate::Layer`]
+    pub layer: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -25,14 +28,20 @@ pub struct DecalContextManager {
     decal_textures:
         std::collections::HashMap<DecalTextureID, (crate::texture::Texture, wgpu::BindGroup)>,
     pub decal_instances: Vec<DecalInstances>,
+    // CPU copies of the textures, used to composite the screenshots. They are only kept when
+    // `capture` is set
+    rasterizer: software::Rasterizer,
+    capture: bool,
     vertex_vector: gpu_vector::GpuVector<[Vertex; 4]>,
     cpu_vertex_vector: Vec<[Vertex; 4]>,
     buffer_index: wgpu::Buffer,
 }
 
 impl DecalContextManager {
+    /// Create the manager, `capture` keeps a copy of the textures on the CPU so
+    /// [`DecalContextManager::composite`] can draw the decals
     #[must_use]
-    pub fn new(device: &wgpu::Device) -> (Self, wgpu::CommandBuffer) {
+    pub fn new(device: &wgpu::Device, capture: bool) -> (Self, wgpu::CommandBuffer) {
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("decal"),
         });
@@ -71,6 +80,8 @@ impl DecalContextManager {
                 vertex_vector,
                 decal_textures: std::collections::HashMap::with_capacity(64),
                 decal_instances: Vec::with_capacity(128),
+                rasterizer: software::Rasterizer::new(),
+                capture,
                 cpu_vertex_vector: Vec::with_capacity(128),
             },
             encoder.finish(),
@@ -79,12 +90,44 @@ impl DecalContextManager {
     pub fn add_instance(&mut self, decal: DecalInstances) {
         self.decal_instances.push(decal);
     }
+    /// Draw the layers and the queued instances onto `target` on the CPU, without consuming them.
+    /// The decals are left out without `capture`
+    pub fn composite(&self, target: &mut [u8], target_size: (u32, u32), layers: &[crate::Layer]) {
+        self.rasterizer
+            .draw_layers(target, target_size, &self.decal_instances, layers);
+    }
+
+    /// Upload the vertices of the queued instances, grouped by layer, so they can be drawn with
+    /// [`DrawDecals::draw_decals`]
+    pub fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.decal_instances.sort_by_key(|instance| instance.layer);
+        for decal_instance in &self.decal_instances {
+            self.cpu_vertex_vector.push(std::array::from_fn(|i| Vertex {
+                position: [decal_instance.pos[i].0, decal_instance.pos[i].1, 0.0],
+                tex_coords: [
+                    decal_instance.uv[i].0,
+                    decal_instance.uv[i].1,
+                    decal_instance.w[i],
+                ],
+                tint: decal_instance.tint,
+            }));
+        }
+
+        let command = self.vertex_vector.sync(device, &self.cpu_vertex_vector);
+        queue.submit(std::iter::once(command));
+    }
+
+    /// Forget the instances once the frame was drawn
+    pub fn clear(&mut self) {
+        self.decal_instances.clear();
+        self.cpu_vertex_vector.clear();
+    }
 }
 
 #[derive(Debug, Clone)]
-struct DecalIDGenerator(DecalTextureID);
+pub(crate) struct DecalIDGenerator(pub(crate) DecalTextureID);
 impl DecalIDGenerator {
-    fn get(&mut self) -> usize {
+    pub(crate) fn get(&mut self) -> usize {
         self.0 += 1;
         self.0
     }
@@ -109,16 +152,24 @@ impl Decal {
             label: Some("decal_bindgroup"),
         });
         ctx.dcm.decal_textures.insert(id, (tex, bind_group));
+        if ctx.dcm.capture {
+            ctx.dcm.rasterizer.add_texture(id, sprite);
+        }
 
+        Self::with_id(id, sprite.1)
+    }
+
+    pub(crate) fn with_id(id: DecalTextureID, size: (u32, u32)) -> Self {
         Self {
             id,
-            size: sprite.1,
-            uv_scale: (1.0 / (sprite.1).0 as f32, 1.0 / (sprite.1).1 as f32),
+            size,
+            uv_scale: (1.0 / size.0 as f32, 1.0 / size.1 as f32),
         }
     }
 
     pub fn destroy(&self, ctx: &mut crate::Context) {
         ctx.dcm.decal_textures.remove(&self.id);
+        ctx.dcm.rasterizer.remove_texture(self.id);
     }
 
     #[must_use]
@@ -127,81 +178,25 @@ impl Decal {
     }
 }
 
-pub trait DrawDecals<'a, 'b>
-where
-    'b: 'a,
-{
-    fn draw_decals(
-        &mut self,
-        dcm: &'b mut DecalContextManager,
-        device: &'b mut wgpu::Device,
-        queue: &'b mut wgpu::Queue,
-    );
+pub trait DrawDecals<'a> {
+    /// Draw the instances attached to `layer`, [`DecalContextManager::prepare`] must have been
+    /// called before
+    fn draw_decals(&mut self, dcm: &'a DecalContextManager, layer: usize);
 }
 
-impl<'a, 'b> DrawDecals<'a, 'b> for wgpu::RenderPass<'a>
-where
-    'b: 'a,
-{
-    fn draw_decals(
-        &mut self,
-        dcm: &'b mut DecalContextManager,
-        device: &'b mut wgpu::Device,
-        queue: &'b mut wgpu::Queue,
-    ) {
-        for decal_instance in dcm.decal_instances.iter() {
-            dcm.cpu_vertex_vector.push([
-                Vertex {
-                    position: [decal_instance.pos[0].0, decal_instance.pos[0].1, 0.0],
-                    tex_coords: [
-                        decal_instance.uv[0].0,
-                        decal_instance.uv[0].1,
-                        decal_instance.w[0],
-                    ],
-                    tint: decal_instance.tint,
-                },
-                Vertex {
-                    position: [decal_instance.pos[1].0, decal_instance.pos[1].1, 0.0],
-                    tex_coords: [
-                        decal_instance.uv[1].0,
-                        decal_instance.uv[1].1,
-                        decal_instance.w[1],
-                    ],
-                    tint: decal_instance.tint,
-                },
-                Vertex {
-                    position: [decal_instance.pos[2].0, decal_instance.pos[2].1, 0.0],
-                    tex_coords: [
-                        decal_instance.uv[2].0,
-                        decal_instance.uv[2].1,
-                        decal_instance.w[2],
-                    ],
-                    tint: decal_instance.tint,
-                },
-                Vertex {
-                    position: [decal_instance.pos[3].0, decal_instance.pos[3].1, 0.0],
-                    tex_coords: [
-                        decal_instance.uv[3].0,
-                        decal_instance.uv[3].1,
-                        decal_instance.w[3],
-                    ],
-                    tint: decal_instance.tint,
-                },
-            ]);
-        }
-
-        let command = dcm.vertex_vector.sync(device, &dcm.cpu_vertex_vector);
-        queue.submit(std::iter::once(command));
+impl<'a> DrawDecals<'a> for wgpu::RenderPass<'a> {
+    fn draw_decals(&mut self, dcm: &'a DecalContextManager, layer: usize) {
         let buffer = dcm.vertex_vector.buffer();
 
         for (range, instance) in dcm
             .vertex_vector
             .iter_offsets()
-            .zip(dcm.decal_instances.drain(..))
+            .zip(dcm.decal_instances.iter())
+            .filter(|(_, instance)| instance.layer == layer)
         {
             let Some(texture) = dcm.decal_textures.get(&instance.id) else {
-                    eprintln!("You tried to use a non-valid decal");
-                    continue
+                eprintln!("You tried to use a non-valid decal");
+                continue;
             };
 
             // Update buffers
@@ -211,6 +206,5 @@ where
             self.set_vertex_buffer(0, buffer.slice(range));
             self.draw_indexed(0..(crate::INDICES.len() as u32), 0, 0..1);
         }
-        dcm.cpu_vertex_vector.clear();
     }
 }
@@ -0,0 +1,262 @@
+use super::{DecalInstances, DecalTextureID};
+
+/// A CPU copy of a decal's texture
+struct Texture {
+    data: Vec<u8>,
+    size: (u32, u32),
+}
+
+impl Texture {
+    fn sample(&self, u: f32, v: f32) -> [f32; 4] {
+        sample(&self.data, self.size, u, v)
+    }
+}
+
+/// Nearest sampling, with the coordinates clamped to the edge (like the GPU sampler).
+/// The textures are sRGB, so the color is converted to linear like the GPU does when sampling
+fn sample(data: &[u8], size: (u32, u32), u: f32, v: f32) -> [f32; 4] {
+    let x = ((u * size.0 as f32).floor() as i64).clamp(0, i64::from(size.0) - 1);
+    let y = ((v * size.1 as f32).floor() as i64).clamp(0, i64::from(size.1) - 1);
+    let index = (y as usize * size.0 as usize + x as usize) * 4;
+    [
+        srgb_to_linear(data[index]),
+        srgb_to_linear(data[index + 1]),
+        srgb_to_linear(data[index + 2]),
+        f32::from(data[index + 3]) / 255.0,
+    ]
+}
+
+fn srgb_to_linear(value: u8) -> f32 {
+    let value = f32::from(value) / 255.0;
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let value = if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+    (value * 255.0).round() as u8
+}
+
+/// A vertex once projected in pixel space
+#[derive(Debug, Clone, Copy)]
+struct ScreenVertex {
+    x: f32,
+    y: f32,
+    // UV + q, like the `tex_coords` of the GPU vertex
+    uvq: [f32; 3],
+}
+
+/// Draw [`DecalInstances`] onto an rgba buffer on the CPU.
+///
+/// This does the same thing as the GPU pipeline: the uv are interpolated with the `w` component
+/// to be perspective-correct, the texture is sampled with the nearest texel and multiplied by the
+/// tint. The result is then alpha-blended over the target, in linear space like the GPU does on
+/// its sRGB target.
+#[derive(Default)]
+pub struct Rasterizer {
+    textures: std::collections::HashMap<DecalTextureID, Texture>,
+}
+
+impl Rasterizer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep a copy of the texture, so instances using this id can be drawn
+    pub fn add_texture(&mut self, id: DecalTextureID, spr: (&[u8], (u32, u32))) {
+        self.textures.insert(
+            id,
+            Texture {
+                data: spr.0.to_vec(),
+                size: spr.1,
+            },
+        );
+    }
+
+    pub fn remove_texture(&mut self, id: DecalTextureID) {
+        self.textures.remove(&id);
+    }
+
+    /// Draw every instance, in order, onto the `target` rgba buffer of size `target_size`
+    /// Instances with an unknown texture are skipped
+    pub fn draw_instances<'a>(
+        &self,
+        target: &mut [u8],
+        target_size: (u32, u32),
+        instances: impl IntoIterator<Item = &'a DecalInstances>,
+    ) {
+        for instance in instances {
+            let Some(texture) = self.textures.get(&instance.id) else {
+                continue;
+            };
+            let vertices: Vec<ScreenVertex> = (0..4)
+                .map(|i| ScreenVertex {
+                    x: (instance.pos[i].0 + 1.0) * 0.5 * target_size.0 as f32,
+                    y: (1.0 - instance.pos[i].1) * 0.5 * target_size.1 as f32,
+                    uvq: [instance.uv[i].0, instance.uv[i].1, instance.w[i]],
+                })
+                .collect();
+            for tri in crate::INDICES.chunks_exact(3) {
+                draw_triangle(
+                    target,
+                    target_size,
+                    texture,
+                    instance.tint,
+                    [
+                        vertices[tri[0] as usize],
+                        vertices[tri[1] as usize],
+                        vertices[tri[2] as usize],
+                    ],
+                );
+            }
+        }
+    }
+
+    /// Draw the enabled layers over the `target` screen, in order, each followed by the instances
+    /// attached to it. The instances attached to the screen are drawn first
+    pub fn draw_layers(
+        &self,
+        target: &mut [u8],
+        target_size: (u32, u32),
+        instances: &[DecalInstances],
+        layers: &[crate::Layer],
+    ) {
+        let attached = |layer: usize| instances.iter().filter(move |i| i.layer == layer);
+        self.draw_instances(target, target_size, attached(0));
+        for (index, layer) in layers.iter().enumerate() {
+            if layer.enabled {
+                draw_layer(target, target_size, layer);
+                self.draw_instances(target, target_size, attached(index + 1));
+            }
+        }
+    }
+}
+
+/// Draw a layer, stretched by its scale and sampled with the nearest texel like the GPU does
+fn draw_layer(target: &mut [u8], target_size: (u32, u32), layer: &crate::Layer) {
+    let (width, height) = (target_size.0 as f32, target_size.1 as f32);
+    let (layer_width, layer_height) = (width * layer.scale.0, height * layer.scale.1);
+    if layer_width <= 0.0 || layer_height <= 0.0 {
+        return;
+    }
+    for y in 0..target_size.1 {
+        let v = (y as f32 + 0.5 - layer.offset.1) / layer_height;
+        if !(0.0..1.0).contains(&v) {
+            continue;
+        }
+        for x in 0..target_size.0 {
+            let u = (x as f32 + 0.5 - layer.offset.0) / layer_width;
+            if !(0.0..1.0).contains(&u) {
+                continue;
+            }
+            let texel = sample(layer.data, target_size, u, v);
+            let src = [
+                texel[0] * layer.tint[0],
+                texel[1] * layer.tint[1],
+                texel[2] * layer.tint[2],
+                texel[3] * layer.tint[3],
+            ];
+            let index = (y as usize * target_size.0 as usize + x as usize) * 4;
+            blend(&mut target[index..index + 4], src);
+        }
+    }
+}
+
+fn edge(a: ScreenVertex, b: ScreenVertex, p: (f32, f32)) -> f32 {
+    (b.x - a.x) * (p.1 - a.y) - (b.y - a.y) * (p.0 - a.x)
+}
+
+/// Top-left fill rule, so pixels on the edge shared by the two triangles are only drawn once
+fn is_top_left(a: ScreenVertex, b: ScreenVertex) -> bool {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    (dy == 0.0 && dx > 0.0) || dy < 0.0
+}
+
+fn draw_triangle(
+    target: &mut [u8],
+    target_size: (u32, u32),
+    texture: &Texture,
+    tint: [f32; 4],
+    mut tri: [ScreenVertex; 3],
+) {
+    let mut area = edge(tri[0], tri[1], (tri[2].x, tri[2].y));
+    if area == 0.0 {
+        return;
+    }
+    if area < 0.0 {
+        tri.swap(1, 2);
+        area = -area;
+    }
+    let edges = [(tri[1], tri[2]), (tri[2], tri[0]), (tri[0], tri[1])];
+    let top_left = edges.map(|(a, b)| is_top_left(a, b));
+
+    let (mut min_x, mut min_y) = (f32::INFINITY, f32::INFINITY);
+    let (mut max_x, mut max_y) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for v in &tri {
+        min_x = min_x.min(v.x);
+        min_y = min_y.min(v.y);
+        max_x = max_x.max(v.x);
+        max_y = max_y.max(v.y);
+    }
+    let min_x = min_x.floor().max(0.0) as u32;
+    let min_y = min_y.floor().max(0.0) as u32;
+    let max_x = (max_x.ceil().max(0.0) as u32).min(target_size.0);
+    let max_y = (max_y.ceil().max(0.0) as u32).min(target_size.1);
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let p = (x as f32 + 0.5, y as f32 + 0.5);
+            let mut bary = [0.0f32; 3];
+            let mut inside = true;
+            for (i, (a, b)) in edges.iter().enumerate() {
+                let e = edge(*a, *b, p);
+                if e < 0.0 || (e == 0.0 && !top_left[i]) {
+                    inside = false;
+                    break;
+                }
+                bary[i] = e / area;
+            }
+            if !inside {
+                continue;
+            }
+            let mut uvq = [0.0f32; 3];
+            for (k, value) in uvq.iter_mut().enumerate() {
+                *value =
+                    bary[0] * tri[0].uvq[k] + bary[1] * tri[1].uvq[k] + bary[2] * tri[2].uvq[k];
+            }
+            if uvq[2] == 0.0 {
+                continue;
+            }
+            let texel = texture.sample(uvq[0] / uvq[2], uvq[1] / uvq[2]);
+            let src = [
+                texel[0] * tint[0],
+                texel[1] * tint[1],
+                texel[2] * tint[2],
+                texel[3] * tint[3],
+            ];
+            let index = (y as usize * target_size.0 as usize + x as usize) * 4;
+            blend(&mut target[index..index + 4], src);
+        }
+    }
+}
+
+/// Alpha blend the linear `src` over the sRGB `dst` pixel
+fn blend(dst: &mut [u8], src: [f32; 4]) {
+    let alpha = src[3].clamp(0.0, 1.0);
+    let inverse_alpha = 1.0 - alpha;
+    for c in 0..3 {
+        let value = src[c].clamp(0.0, 1.0) * alpha + srgb_to_linear(dst[c]) * inverse_alpha;
+        dst[c] = linear_to_srgb(value);
+    }
+    let value = alpha * 255.0 + f32::from(dst[3]) * inverse_alpha;
+    dst[3] = value.round() as u8;
+}
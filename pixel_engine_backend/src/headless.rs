@@ -0,0 +1,67 @@
+use crate::decals;
+
+/// A Context that doesn't need a window nor a GPU.
+/// The presented frame is kept in memory so it can be read back afterwards.
+pub struct Context {
+    size: (u32, u32),
+    frame: Vec<u8>,
+    id_generator: decals::DecalIDGenerator,
+    decal_instances: Vec<decals::DecalInstances>,
+    rasterizer: decals::software::Rasterizer,
+}
+
+impl Context {
+    #[must_use]
+    pub fn new(px_size: (u32, u32, u32)) -> Self {
+        Self {
+            size: (px_size.0, px_size.1),
+            frame: [0, 0, 0, 255].repeat((px_size.0 * px_size.1) as usize),
+            id_generator: decals::DecalIDGenerator(0),
+            decal_instances: Vec::with_capacity(128),
+            rasterizer: decals::software::Rasterizer::new(),
+        }
+    }
+
+    /// Present the given screen data with the decals drawn on top of it, it will become the
+    /// current frame
+    pub fn render(&mut self, data: &[u8]) {
+        self.render_layers(data, &[]);
+    }
+
+    /// Present the given screen data with the layers drawn over it, in order, each with the decals
+    /// attached to it. It will become the current frame
+    pub fn render_layers(&mut self, data: &[u8], layers: &[crate::Layer]) {
+        self.frame.copy_from_slice(data);
+        self.rasterizer
+            .draw_layers(&mut self.frame, self.size, &self.decal_instances, layers);
+        self.decal_instances.clear();
+    }
+
+    /// Compose the given screen data and layers with the decals queued so far, as the next
+    /// [`Context::render_layers`] would present it
+    #[must_use]
+    pub fn composite(&self, data: &[u8], layers: &[crate::Layer]) -> Vec<u8> {
+        let mut frame = data.to_vec();
+        self.rasterizer
+            .draw_layers(&mut frame, self.size, &self.decal_instances, layers);
+        frame
+    }
+
+    /// Get the last presented frame with its size (in pixel)
+    #[must_use]
+    pub fn frame(&self) -> (&[u8], (u32, u32)) {
+        (&self.frame, self.size)
+    }
+
+    pub fn create_decal(&mut self, spr: (&[u8], (u32, u32))) -> decals::Decal {
+        let decal = decals::Decal::with_id(self.id_generator.get(), spr.1);
+        self.rasterizer.add_texture(decal.id(), spr);
+        decal
+    }
+    pub fn destroy_decal(&mut self, decal: &decals::Decal) {
+        self.rasterizer.remove_texture(decal.id());
+    }
+    pub fn draw_decal_instance(&mut self, decal_instance: decals::DecalInstances) {
+        self.decal_instances.push(decal_instance);
+    }
+}
@@ -1,9 +1,13 @@
 use bytemuck::{Pod, Zeroable};
 use wgpu::util::DeviceExt;
+pub use wgpu::PresentMode;
 pub use winit;
 use winit::window::Window;
 pub mod decals;
+pub mod headless;
 mod texture;
+mod viewport;
+pub use viewport::{Scaling, Viewport};
 
 #[macro_use]
 mod macros {
@@ -95,6 +99,8 @@ pub struct Context {
     config: wgpu::SurfaceConfiguration,
 
     render_pipeline: wgpu::RenderPipeline,
+    blend_pipeline: wgpu::RenderPipeline,
+    layers: Vec<LayerTexture>,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     num_indices: u32,
@@ -102,10 +108,69 @@ pub struct Context {
     main_bind_group: wgpu::BindGroup,
     bind_group_layout: wgpu::BindGroupLayout,
     dcm: decals::DecalContextManager,
+    scaling: Scaling,
+    viewport: Viewport,
+}
+
+/// The GPU side of a [`Layer`]
+struct LayerTexture {
+    texture: texture::Texture,
+    bind_group: wgpu::BindGroup,
+    vertex_buffer: wgpu::Buffer,
+}
+
+/// The options of a [`Context`] that can't be deduced from its window
+#[derive(Debug, Clone, Copy)]
+pub struct ContextOptions {
+    /// How the frames are synced with the screen, falls back to [`PresentMode::Fifo`] if the
+    /// surface doesn't support it
+    pub present_mode: PresentMode,
+    /// How the screen is scaled when the window is bigger than it
+    pub scaling: Scaling,
+    /// Keep a copy of every decal texture on the CPU, so [`Context::composite`] includes the
+    /// decals. Without it, the composited frames only have the screen and the layers
+    pub capture: bool,
+}
+
+impl Default for ContextOptions {
+    fn default() -> Self {
+        Self {
+            present_mode: PresentMode::Fifo,
+            scaling: Scaling::Integer,
+            capture: false,
+        }
+    }
+}
+
+/// A layer drawn over the screen, and over the layers before it, when presenting a frame
+#[derive(Debug, Clone, Copy)]
+pub struct Layer<'a> {
+    /// The rgba pixels of the layer, it has the size of the screen
+    pub data: &'a [u8],
+    /// A disabled layer isn't drawn, nor are the decals attached to it
+    pub enabled: bool,
+    /// Where the top left corner of the layer is drawn on the screen, in pixels
+    pub offset: (f32, f32),
+    /// How much the layer is stretched horizontally and vertically
+    pub scale: (f32, f32),
+    /// The colour multiplied with the layer's pixels
+    pub tint: [f32; 4],
+}
+
+impl Layer<'_> {
+    /// The corners of the layer in normalized space, in the order of [`VERTICES`]
+    pub(crate) fn corners(&self, screen: (u32, u32)) -> [(f32, f32); 4] {
+        let (width, height) = (screen.0 as f32, screen.1 as f32);
+        let left = self.offset.0 / width * 2.0 - 1.0;
+        let top = 1.0 - self.offset.1 / height * 2.0;
+        let right = left + self.scale.0 * 2.0;
+        let bottom = top - self.scale.1 * 2.0;
+        [(left, top), (left, bottom), (right, bottom), (right, top)]
+    }
 }
 
 impl Context {
-    pub async fn new(window: &Window, px_size: (u32, u32, u32)) -> Self {
+    pub async fn new(window: &Window, px_size: (u32, u32, u32), options: ContextOptions) -> Self {
         let size = window.inner_size();
         let instance = wgpu::Instance::new(wgpu::Backends::all());
 
@@ -137,12 +202,19 @@ impl Context {
 
         device.on_uncaptured_error(|error| panic!("[WGPU Error] {}", error));
 
+        // The automatic modes always fall back to something supported
+        let supported_modes = surface.get_supported_present_modes(&adapter);
+        let present_mode = match options.present_mode {
+            mode @ (PresentMode::AutoVsync | PresentMode::AutoNoVsync) => mode,
+            mode if supported_modes.contains(&mode) => mode,
+            _ => PresentMode::Fifo,
+        };
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: surface.get_supported_formats(&adapter)[0],
             width: size.width,
             height: size.height,
-            present_mode: wgpu::PresentMode::Fifo,
+            present_mode,
             alpha_mode: wgpu::CompositeAlphaMode::Auto,
         };
 
@@ -222,58 +294,63 @@ impl Context {
                 push_constant_ranges: &[],
             });
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &vs_module,
-                entry_point: "main",
-                buffers: &[Vertex::desc()],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &fs_module,
-                entry_point: "main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    #[cfg(target_arch = "wasm32")]
-                    format: wgpu::TextureFormat::Rgba8UnormSrgb,
-                    #[cfg(not(target_arch = "wasm32"))]
-                    format: wgpu::TextureFormat::Bgra8UnormSrgb,
-                    write_mask: wgpu::ColorWrites::ALL,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                })],
-            }),
-            depth_stencil: None,
-            multiview: None,
-
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList, // 1.
-                strip_index_format: None,                        //
-                front_face: wgpu::FrontFace::Ccw,                // 2.
-                cull_mode: None,
-                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            multisample: wgpu::MultisampleState {
-                count: 1,                         // 2.
-                mask: !0,                         // 3.
-                alpha_to_coverage_enabled: false, // 4.
-            },
-            // color_states: &[wgpu::ColorStateDescriptor {
-            //     format: sc_desc.format,
-            //     color_blend: wgpu::BlendDescriptor::REPLACE,
-            //     alpha_blend: wgpu::BlendDescriptor::REPLACE,
-            //     write_mask: wgpu::ColorWrite::ALL,
-            // }],
-            // vertex_state: wgpu::VertexStateDescriptor {
-            //     index_format: wgpu::IndexFormat::Uint16,
-            //     vertex_buffers: &[Vertex::desc()],
-            // },
-            // sample_count: 1,
-            // sample_mask: !0,
-            // alpha_to_coverage_enabled: false,
-        });
+        let create_pipeline = |label, blend| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&render_pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &vs_module,
+                    entry_point: "main",
+                    buffers: &[Vertex::desc()],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &fs_module,
+                    entry_point: "main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        #[cfg(target_arch = "wasm32")]
+                        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                        #[cfg(not(target_arch = "wasm32"))]
+                        format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                        write_mask: wgpu::ColorWrites::ALL,
+                        blend: Some(blend),
+                    })],
+                }),
+                depth_stencil: None,
+                multiview: None,
+
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList, // 1.
+                    strip_index_format: None,                        //
+                    front_face: wgpu::FrontFace::Ccw,                // 2.
+                    cull_mode: None,
+                    // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                multisample: wgpu::MultisampleState {
+                    count: 1,                         // 2.
+                    mask: !0,                         // 3.
+                    alpha_to_coverage_enabled: false, // 4.
+                },
+                // color_states: &[wgpu::ColorStateDescriptor {
+                //     format: sc_desc.format,
+                //     color_blend: wgpu::BlendDescriptor::REPLACE,
+                //     alpha_blend: wgpu::BlendDescriptor::REPLACE,
+                //     write_mask: wgpu::ColorWrite::ALL,
+                // }],
+                // vertex_state: wgpu::VertexStateDescriptor {
+                //     index_format: wgpu::IndexFormat::Uint16,
+                //     vertex_buffers: &[Vertex::desc()],
+                // },
+                // sample_count: 1,
+                // sample_mask: !0,
+                // alpha_to_coverage_enabled: false,
+            })
+        };
+        let render_pipeline = create_pipeline("pipeline", wgpu::BlendState::REPLACE);
+        // The decals and the layers are blended over the screen, like the software rasterizer does
+        let blend_pipeline = create_pipeline("blend_pipeline", wgpu::BlendState::ALPHA_BLENDING);
         let encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Render Encoder"),
         });
@@ -294,13 +371,22 @@ impl Context {
         });
         queue.submit(std::iter::once(encoder.finish()));
         let num_indices = INDICES.len() as u32;
-        let (dcm, cmd) = decals::DecalContextManager::new(&device);
+        let (dcm, cmd) = decals::DecalContextManager::new(&device, options.capture);
         queue.submit(std::iter::once(cmd));
+        let viewport = Viewport::new(
+            (config.width, config.height),
+            (px_size.0, px_size.1),
+            options.scaling,
+        );
         Self {
+            scaling: options.scaling,
+            viewport,
             surface,
             device,
             queue,
             render_pipeline,
+            blend_pipeline,
+            layers: Vec::new(),
             vertex_buffer,
             config,
             index_buffer,
@@ -313,7 +399,14 @@ impl Context {
     }
 
     pub fn render(&mut self, data: &[u8]) {
+        self.render_layers(data, &[]);
+    }
+
+    /// Present the screen with the given layers drawn over it, in order
+    pub fn render_layers(&mut self, data: &[u8], layers: &[Layer]) {
         self.main_texture.update(&self.queue, data);
+        self.update_layers(layers);
+        self.dcm.prepare(&self.device, &self.queue);
         if let Ok(frame) = self.surface.get_current_texture() {
             //.expect("Timeout getting texture");
 
@@ -332,7 +425,8 @@ impl Context {
                         view: &view,
                         resolve_target: None,
                         ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                            // The letterbox bars
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
                             store: true,
                         },
                     })],
@@ -341,17 +435,125 @@ impl Context {
                 });
                 use decals::DrawDecals;
 
+                #[allow(clippy::cast_precision_loss)]
+                render_pass.set_viewport(
+                    self.viewport.x as f32,
+                    self.viewport.y as f32,
+                    self.viewport.width as f32,
+                    self.viewport.height as f32,
+                    0.0,
+                    1.0,
+                );
                 render_pass.set_pipeline(&self.render_pipeline);
                 render_pass.set_bind_group(0, &self.main_bind_group, &[]);
                 render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
                 render_pass
                     .set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
                 render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
-                render_pass.draw_decals(&mut self.dcm, &mut self.device, &mut self.queue);
+                render_pass.set_pipeline(&self.blend_pipeline);
+                render_pass.draw_decals(&self.dcm, 0);
+                for (index, (layer, texture)) in layers.iter().zip(&self.layers).enumerate() {
+                    if !layer.enabled {
+                        continue;
+                    }
+                    render_pass.set_bind_group(0, &texture.bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, texture.vertex_buffer.slice(..));
+                    render_pass
+                        .set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                    render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+                    render_pass.draw_decals(&self.dcm, index + 1);
+                }
             }
             self.queue.submit(std::iter::once(encoder.finish()));
             frame.present();
         }
+        self.dcm.clear();
+    }
+
+    /// Upload the enabled layers and their corners, creating their textures when needed
+    fn update_layers(&mut self, layers: &[Layer]) {
+        let size = self.main_texture.size;
+        while self.layers.len() < layers.len() {
+            let texture = texture::Texture::from_bytes(
+                &self.device,
+                &self.queue,
+                (
+                    &vec![0; (size.width * size.height * 4) as usize],
+                    (size.width, size.height),
+                ),
+            );
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&texture.view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&texture.sampler),
+                    },
+                ],
+                label: Some("layer_bind_group"),
+            });
+            let vertex_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("layer_vertex_buffer"),
+                size: VERTEX_BUFFER_SIZE,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::VERTEX,
+                mapped_at_creation: false,
+            });
+            self.layers.push(LayerTexture {
+                texture,
+                bind_group,
+                vertex_buffer,
+            });
+        }
+        for (layer, gpu_layer) in layers.iter().zip(&self.layers) {
+            if !layer.enabled {
+                continue;
+            }
+            gpu_layer.texture.update(&self.queue, layer.data);
+            let corners = layer.corners((size.width, size.height));
+            let vertices: [Vertex; 4] = std::array::from_fn(|i| Vertex {
+                position: [corners[i].0, corners[i].1, 0.0],
+                tex_coords: VERTICES[i].tex_coords,
+                tint: layer.tint,
+            });
+            self.queue
+                .write_buffer(&gpu_layer.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+        }
+    }
+
+    /// Reconfigure the surface after the window was resized
+    pub fn resize(&mut self, size: (u32, u32)) {
+        // A minimized window has a size of 0, which isn't a valid surface
+        if size.0 == 0 || size.1 == 0 {
+            return;
+        }
+        self.config.width = size.0;
+        self.config.height = size.1;
+        self.surface.configure(&self.device, &self.config);
+        self.update_viewport();
+    }
+
+    pub fn set_scaling(&mut self, scaling: Scaling) {
+        self.scaling = scaling;
+        self.update_viewport();
+    }
+
+    /// Where the screen is drawn on the window
+    #[must_use]
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    fn update_viewport(&mut self) {
+        let screen = self.main_texture.size;
+        self.viewport = Viewport::new(
+            (self.config.width, self.config.height),
+            (screen.width, screen.height),
+            self.scaling,
+        );
     }
 
     pub fn create_decal(&mut self, spr: (&[u8], (u32, u32))) -> decals::Decal {
@@ -360,6 +562,17 @@ impl Context {
     pub fn draw_decal_instance(&mut self, decal_instance: decals::DecalInstances) {
         self.dcm.add_instance(decal_instance);
     }
+    /// Compose the given screen data and layers with the decals queued so far, as the next
+    /// [`Context::render_layers`] would present it. The decals are only included with
+    /// [`ContextOptions::capture`]
+    #[must_use]
+    pub fn composite(&self, data: &[u8], layers: &[Layer]) -> Vec<u8> {
+        let mut frame = data.to_vec();
+        let size = self.main_texture.size;
+        self.dcm
+            .composite(&mut frame, (size.width, size.height), layers);
+        frame
+    }
 }
 
 /*
@@ -0,0 +1,65 @@
+/// How the screen is scaled to fit the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scaling {
+    /// The biggest whole scale that fits, so every pixel has the same size.
+    /// If the window is smaller than the screen, it falls back to [`Scaling::Fractional`]
+    Integer,
+    /// The biggest scale that fits, pixels may differ by one physical pixel
+    Fractional,
+}
+
+/// The area of the window (in physical pixels) where the screen is drawn,
+/// the rest of the window is filled with black bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Viewport {
+    /// Center the `screen` (in pixel) inside the `window` (in physical pixels)
+    #[must_use]
+    pub fn new(window: (u32, u32), screen: (u32, u32), scaling: Scaling) -> Self {
+        if window.0 == 0 || window.1 == 0 || screen.0 == 0 || screen.1 == 0 {
+            return Self {
+                x: 0,
+                y: 0,
+                width: window.0,
+                height: window.1,
+            };
+        }
+        let integer_scale = (window.0 / screen.0).min(window.1 / screen.1);
+        let (width, height) = if scaling == Scaling::Integer && integer_scale >= 1 {
+            (screen.0 * integer_scale, screen.1 * integer_scale)
+        } else {
+            let scale = (f64::from(window.0) / f64::from(screen.0))
+                .min(f64::from(window.1) / f64::from(screen.1));
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            (
+                ((f64::from(screen.0) * scale).round() as u32).clamp(1, window.0),
+                ((f64::from(screen.1) * scale).round() as u32).clamp(1, window.1),
+            )
+        };
+        Self {
+            x: (window.0 - width) / 2,
+            y: (window.1 - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    /// Map a position on the window (in physical pixels) to a position on the `screen` (in pixel)
+    /// The result is outside of the screen if the position is on the black bars
+    #[must_use]
+    pub fn to_screen(&self, pos: (f64, f64), screen: (u32, u32)) -> (f64, f64) {
+        if self.width == 0 || self.height == 0 {
+            return (0.0, 0.0);
+        }
+        (
+            (pos.0 - f64::from(self.x)) * f64::from(screen.0) / f64::from(self.width),
+            (pos.1 - f64::from(self.y)) * f64::from(screen.1) / f64::from(self.height),
+        )
+    }
+}
@@ -0,0 +1,48 @@
+use pixel_engine_backend::{Scaling, Viewport};
+
+fn viewport(x: u32, y: u32, width: u32, height: u32) -> Viewport {
+    Viewport {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+#[test]
+fn integer_scaling_letterboxes() {
+    // 3x fits horizontally but not vertically, 2x is used and centered
+    assert_eq!(
+        Viewport::new((330, 250), (100, 100), Scaling::Integer),
+        viewport(65, 25, 200, 200)
+    );
+    assert_eq!(
+        Viewport::new((1920, 1080), (320, 180), Scaling::Integer),
+        viewport(0, 0, 1920, 1080)
+    );
+}
+
+#[test]
+fn fractional_scaling_fills_one_axis() {
+    assert_eq!(
+        Viewport::new((330, 250), (100, 100), Scaling::Fractional),
+        viewport(40, 0, 250, 250)
+    );
+}
+
+#[test]
+fn smaller_window_shrinks_the_screen() {
+    assert_eq!(
+        Viewport::new((50, 100), (100, 100), Scaling::Integer),
+        viewport(0, 25, 50, 50)
+    );
+}
+
+#[test]
+fn mouse_goes_through_the_viewport() {
+    let vp = Viewport::new((330, 250), (100, 100), Scaling::Integer);
+    assert_eq!(vp.to_screen((65.0, 25.0), (100, 100)), (0.0, 0.0));
+    assert_eq!(vp.to_screen((264.0, 224.0), (100, 100)), (99.5, 99.5));
+    let (x, _) = vp.to_screen((10.0, 100.0), (100, 100));
+    assert!(x < 0.0);
+}
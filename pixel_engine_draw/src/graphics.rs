@@ -9,10 +9,10 @@ use std::{cell::UnsafeCell, convert::TryInto, mem::transmute, sync::Arc};
 use once_cell::sync::OnceCell;
 use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
 
-use crate::vector2::{Vi2d, Vu2d};
+use crate::vector2::{Vf2d, Vi2d, Vu2d};
 
 /// The Drawing Mode used
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone)]
 pub enum PixelMode {
     /// Basic drawing mode
     /// The pixel data after the draw will be the same as the pixel given
@@ -22,6 +22,85 @@ pub enum PixelMode {
     Alpha,
     /// Will draw only if the alpha is equals to 255
     Mask,
+    /// Add the color to the pixel, weighted by its alpha and the blend factor
+    Additive,
+    /// Subtract the color from the pixel, weighted by its alpha and the blend factor
+    Subtract,
+    /// Multiply the pixel by the color, darkening it
+    Multiply,
+    /// Inverse of [`PixelMode::Multiply`], lightening the pixel
+    Screen,
+    /// Keep the darkest of the color and the pixel, for each channel
+    Min,
+    /// Keep the lightest of the color and the pixel, for each channel
+    Max,
+    /// Xor the red, green and blue of the color with the pixel, drawing twice the same thing
+    /// erases it. The alpha of the color is ignored
+    Xor,
+    /// Use the given function to compute the new pixel,
+    /// it is given `(x, y, color drawn, current pixel)`
+    Custom(fn(u32, u32, Color, Color) -> Color),
+}
+
+impl PartialEq for PixelMode {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            // Compared by address, the same function can have several
+            (PixelMode::Custom(f), PixelMode::Custom(g)) => *f as usize == *g as usize,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for PixelMode {}
+
+impl PixelMode {
+    /// Compute the color of the pixel at `pos` when `src` is drawn over `dst`, as
+    /// [`SmartDrawingTrait::draw`](crate::traits::SmartDrawingTrait::draw) does.
+    /// Returns `None` when the pixel is left untouched
+    #[must_use]
+    pub fn blend(self, pos: Vu2d, src: Color, dst: Color, blend_factor: f32) -> Option<Color> {
+        let alpha: f32 = (f32::from(src.a) / 255.0f32) * blend_factor;
+        // Move each channel of dst toward op(src, dst) by alpha
+        let mix = |op: fn(f32, f32) -> f32| {
+            let channel = |src: u8, dst: u8| {
+                let (src, dst) = (f32::from(src), f32::from(dst));
+                (dst + alpha * (op(src, dst) - dst)).clamp(0.0, 255.0) as u8
+            };
+            Color::new_with_alpha(
+                channel(src.r, dst.r),
+                channel(src.g, dst.g),
+                channel(src.b, dst.b),
+                dst.a,
+            )
+        };
+        match self {
+            PixelMode::Normal => Some(src),
+            PixelMode::Mask => (src.a == 255).then_some(src),
+            PixelMode::Alpha => {
+                let inverse_alpha: f32 = 1.0 - alpha;
+                let red: f32 = alpha * f32::from(src.r) + inverse_alpha * f32::from(dst.r);
+                let green: f32 = alpha * f32::from(src.g) + inverse_alpha * f32::from(dst.g);
+                let blue: f32 = alpha * f32::from(src.b) + inverse_alpha * f32::from(dst.b);
+                Some(Color::new(red as u8, green as u8, blue as u8))
+            }
+            PixelMode::Additive => Some(mix(|src, dst| dst + src)),
+            PixelMode::Subtract => Some(mix(|src, dst| dst - src)),
+            PixelMode::Multiply => Some(mix(|src, dst| dst * src / 255.0)),
+            PixelMode::Screen => Some(mix(|src, dst| {
+                255.0 - (255.0 - dst) * (255.0 - src) / 255.0
+            })),
+            PixelMode::Min => Some(mix(f32::min)),
+            PixelMode::Max => Some(mix(f32::max)),
+            PixelMode::Xor => Some(Color::new_with_alpha(
+                dst.r ^ src.r,
+                dst.g ^ src.g,
+                dst.b ^ src.b,
+                dst.a,
+            )),
+            PixelMode::Custom(f) => Some(f(pos.x, pos.y, src, dst)),
+        }
+    }
 }
 
 /// Represent a Sprite
@@ -105,6 +184,25 @@ impl std::fmt::Display for OverlappingError {
 impl std::error::Error for OverlappingError {}
 
 impl Sprite {
+    /// Draw on the sprite with every drawing trait, [`ShapesTrait`](crate::traits::ShapesTrait),
+    /// [`SpriteTrait`](crate::traits::SpriteTrait)..., through a [`DrawingSprite`] that lives
+    /// as long as `f`. It starts with [`PixelMode::Normal`], no clip and no transform.
+    ///
+    /// ```
+    /// # use pixel_engine_draw::graphics::{Color, PixelMode, Sprite};
+    /// # use pixel_engine_draw::traits::*;
+    /// let mut tile = Sprite::new_with_color(8, 8, Color::BLANK);
+    /// tile.draw_with(|target| {
+    ///     target.fill_rect((0, 0), (8, 8), Color::DARK_GREEN);
+    ///     target.set_pixel_mode(PixelMode::Additive);
+    ///     target.draw((4, 4), Color::DARK_RED);
+    /// });
+    /// assert_eq!(tile.get_pixel(4, 4), Color::new(128, 128, 0));
+    /// ```
+    pub fn draw_with<R>(&mut self, f: impl FnOnce(&mut DrawingSprite<&mut Sprite>) -> R) -> R {
+        f(&mut DrawingSprite::new(self))
+    }
+
     pub fn size(&self) -> &Vu2d {
         &self.size
     }
@@ -286,6 +384,42 @@ impl Sprite {
             read_lock: Arc::new(RwLock::new(())),
         })
     }
+    /// Encode the sprite as a PNG image
+    ///
+    /// # Errors
+    ///
+    /// If the image crate failed to encode the sprite, returns an error
+    pub fn encode_png(&self) -> Result<Vec<u8>, String> {
+        use image::ImageEncoder;
+        let mut bytes = Vec::new();
+        let (raw, lock) = self.get_read_lock();
+        let result = image::codecs::png::PngEncoder::new(&mut bytes).write_image(
+            raw,
+            self.width(),
+            self.height(),
+            image::ColorType::Rgba8,
+        );
+        drop(lock);
+        result.map_err(|err| err.to_string())?;
+        Ok(bytes)
+    }
+
+    /// Save the sprite to an image file, the format is deduced from the file's extension
+    /// # Errors
+    ///
+    /// If the format isn't handled by the image crate or if the file IO failed, returns an error
+    pub fn save_to_file<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), String> {
+        let (raw, lock) = self.get_read_lock();
+        let result = image::save_buffer(
+            path,
+            raw,
+            self.width(),
+            self.height(),
+            image::ColorType::Rgba8,
+        );
+        drop(lock);
+        result.map_err(|err| err.to_string())
+    }
     /// Create [Sprite] with a size of 1x1
     #[must_use]
     pub fn new_blank() -> Sprite {
@@ -364,6 +498,15 @@ impl Sprite {
 }
 
 impl<'spr> SpriteMutRef<'spr> {
+    /// Draw on the subsprite with every drawing trait, like [`Sprite::draw_with`]. The positions
+    /// are relative to the subsprite and nothing is drawn outside of it
+    pub fn draw_with<R>(
+        &mut self,
+        f: impl FnOnce(&mut DrawingSprite<&mut SpriteMutRef<'spr>>) -> R,
+    ) -> R {
+        f(&mut DrawingSprite::new(self))
+    }
+
     fn get_nth_ptr(&self, row: u32) -> *mut u8 {
         unsafe {
             let base_offset = (self.pos.y * self.spr_width + self.pos.x) as usize * 4;
@@ -493,8 +636,19 @@ impl Color {
 struct DrawData {
     pixel_mode: PixelMode,
     blend_factor: f32,
+    /// The clipping rectangles, in pixels of the target: top left (inclusive) and bottom right
+    /// (exclusive) corners. The last one is already the intersection of all of them
+    clips: Vec<(Vi2d, Vi2d)>,
+    /// The transforms, as `(offset, scale)`. The last one is already composed with the previous
+    /// ones
+    transforms: Vec<(Vf2d, f32)>,
 }
 
+/// A target that can be drawn on with every drawing trait: it holds the pixel mode, the blend
+/// factor, the clipping rectangles and the transforms used to draw on `S`.
+///
+/// `S` can be owned, like the engine's screen, or borrowed, see [`Sprite::draw_with`] and
+/// [`SpriteMutRef::draw_with`]
 #[derive(Clone, Debug)]
 pub struct DrawingSprite<S: DrawSpriteTrait> {
     draw_data: DrawData,
@@ -532,46 +686,118 @@ impl<S: DrawSpriteTrait> crate::traits::SmartDrawingTrait for DrawingSprite<S> {
         self.sprite.get_pixel(pos)
     }
     fn draw<P: Into<Vi2d>>(&mut self, pos: P, col: Color) {
-        let pixel_mode = self.get_pixel_mode();
-        let blend_factor = self.get_blend_factor();
-        let pos @ Vi2d { x, y } = pos.into();
-        if x >= self.sprite.size().x.try_into().unwrap()
-            || y >= self.sprite.size().y.try_into().unwrap()
-            || x < 0
-            || y < 0
-        {
+        let pos = pos.into();
+        let Some(&(offset, scale)) = self.draw_data.transforms.last() else {
+            self.draw_pixel(pos, col);
+            return;
+        };
+        // The pixel covers [pos * scale + offset; (pos + 1) * scale + offset), at least one pixel
+        let start = |p: i32, offset: f32| (p as f32 * scale + offset).floor() as i32;
+        let (x0, y0) = (start(pos.x, offset.x), start(pos.y, offset.y));
+        // When shrinking, the blending modes only draw the first pixel that lands on a target
+        // pixel so they don't apply it several times, the others can draw them all and keep the
+        // thin lines
+        let blends = !matches!(
+            self.draw_data.pixel_mode,
+            PixelMode::Normal | PixelMode::Mask | PixelMode::Min | PixelMode::Max
+        );
+        if blends && (start(pos.x - 1, offset.x) == x0 || start(pos.y - 1, offset.y) == y0) {
             return;
         }
-        match pixel_mode {
-            PixelMode::Normal => unsafe {
-                self.sprite.set_pixel_unchecked(pos.cast_u32(), col);
-            },
-            PixelMode::Mask => {
-                if col.a == 255 {
-                    unsafe {
-                        self.sprite.set_pixel_unchecked(pos.cast_u32(), col);
-                    }
-                }
-            }
-            PixelMode::Alpha => {
-                let current_color: Color =
-                    unsafe { self.sprite.get_pixel_unchecked(pos.cast_u32()) };
-                let alpha: f32 = (f32::from(col.a) / 255.0f32) * blend_factor;
-                let inverse_alpha: f32 = 1.0 - alpha;
-                let red: f32 =
-                    alpha * f32::from(col.r) + inverse_alpha * f32::from(current_color.r);
-                let green: f32 =
-                    alpha * f32::from(col.g) + inverse_alpha * f32::from(current_color.g);
-                let blue: f32 =
-                    alpha * f32::from(col.b) + inverse_alpha * f32::from(current_color.b);
-                unsafe {
-                    self.sprite
-                        .set_pixel_unchecked(pos.cast_u32(), [red, green, blue].into());
-                }
+        let x1 = start(pos.x + 1, offset.x).max(x0 + 1);
+        let y1 = start(pos.y + 1, offset.y).max(y0 + 1);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                self.draw_pixel(Vi2d { x, y }, col);
             }
         }
     }
 
+    fn push_clip<P: Into<Vi2d>>(&mut self, pos: P, size: P) {
+        let (pos, size) = (pos.into(), size.into());
+        let (offset, scale) = self
+            .draw_data
+            .transforms
+            .last()
+            .copied()
+            .unwrap_or((Vf2d { x: 0.0, y: 0.0 }, 1.0));
+        let corner = |p: i32, offset: f32| (p as f32 * scale + offset).floor() as i32;
+        let mut min = Vi2d {
+            x: corner(pos.x, offset.x),
+            y: corner(pos.y, offset.y),
+        };
+        let mut max = Vi2d {
+            x: corner(pos.x + size.x, offset.x),
+            y: corner(pos.y + size.y, offset.y),
+        };
+        if let Some((parent_min, parent_max)) = self.draw_data.clips.last() {
+            min = Vi2d {
+                x: min.x.max(parent_min.x),
+                y: min.y.max(parent_min.y),
+            };
+            max = Vi2d {
+                x: max.x.min(parent_max.x),
+                y: max.y.min(parent_max.y),
+            };
+        }
+        self.draw_data.clips.push((min, max));
+    }
+
+    fn pop_clip(&mut self) {
+        self.draw_data.clips.pop();
+    }
+
+    fn push_transform<P: Into<Vi2d>>(&mut self, offset: P, scale: f32) {
+        let offset = offset.into();
+        let transform = match self.draw_data.transforms.last() {
+            Some(&(parent_offset, parent_scale)) => (
+                Vf2d {
+                    x: parent_offset.x + offset.x as f32 * parent_scale,
+                    y: parent_offset.y + offset.y as f32 * parent_scale,
+                },
+                parent_scale * scale,
+            ),
+            None => (
+                Vf2d {
+                    x: offset.x as f32,
+                    y: offset.y as f32,
+                },
+                scale,
+            ),
+        };
+        self.draw_data.transforms.push(transform);
+    }
+
+    fn pop_transform(&mut self) {
+        self.draw_data.transforms.pop();
+    }
+
+    fn get_clip(&self) -> (Vi2d, Vi2d) {
+        let (min, max) = self
+            .draw_data
+            .clips
+            .last()
+            .copied()
+            .unwrap_or((Vi2d { x: 0, y: 0 }, self.sprite.size().cast_i32()));
+        let Some(&(offset, scale)) = self.draw_data.transforms.last() else {
+            return (min, max);
+        };
+        // Back to the coordinates of the transform, including the pixels partly inside
+        let local = |p: i32, offset: f32| (p as f32 - offset) / scale;
+        let (ax, bx) = (local(min.x, offset.x), local(max.x, offset.x));
+        let (ay, by) = (local(min.y, offset.y), local(max.y, offset.y));
+        (
+            Vi2d {
+                x: ax.min(bx).floor() as i32,
+                y: ay.min(by).floor() as i32,
+            },
+            Vi2d {
+                x: ax.max(bx).ceil() as i32,
+                y: ay.max(by).ceil() as i32,
+            },
+        )
+    }
+
     fn get_textsheet(&self) -> &'static Sprite {
         create_text()
     }
@@ -609,12 +835,12 @@ pub trait DrawSpriteTrait {
     /// Get the pixel at the given location, but bypassing any bounds check
     ///
     /// # Safety
-    ///     You must ensure that the pos in bounds
+    /// You must ensure that the pos in bounds
     unsafe fn get_pixel_unchecked(&self, pos: Vu2d) -> Color;
     /// Set the pixel at the given location, but bypassing any bounds check
     ///
     /// # Safety
-    ///     You must ensure that the pos in bounds
+    /// You must ensure that the pos in bounds
     unsafe fn set_pixel_unchecked(&mut self, pos: Vu2d, col: Color);
 }
 
@@ -728,6 +954,24 @@ impl<'spr> DrawSpriteTrait for SpriteMutRef<'spr> {
     }
 }
 
+impl<S: DrawSpriteTrait + ?Sized> DrawSpriteTrait for &mut S {
+    fn get_pixel(&self, pos: Vi2d) -> Option<Color> {
+        (**self).get_pixel(pos)
+    }
+    fn set_pixel(&mut self, pos: Vi2d, col: Color) {
+        (**self).set_pixel(pos, col);
+    }
+    fn size(&self) -> Vu2d {
+        (**self).size()
+    }
+    unsafe fn get_pixel_unchecked(&self, pos: Vu2d) -> Color {
+        (**self).get_pixel_unchecked(pos)
+    }
+    unsafe fn set_pixel_unchecked(&mut self, pos: Vu2d, col: Color) {
+        (**self).set_pixel_unchecked(pos, col);
+    }
+}
+
 impl<S: DrawSpriteTrait> DrawingSprite<S> {
     pub fn new(spr: S) -> Self {
         DrawingSprite {
@@ -743,9 +987,50 @@ impl<S: DrawSpriteTrait> DrawingSprite<S> {
         &self.sprite
     }
 
-    pub fn get_mut(&mut self) -> &S {
+    pub fn get_mut(&mut self) -> &mut S {
         &mut self.sprite
     }
+
+    /// Exchange the pixel mode, blend factor, clipping rectangles and transforms with `other`,
+    /// to keep drawing the same way on another target
+    pub fn swap_draw_state<T: DrawSpriteTrait>(&mut self, other: &mut DrawingSprite<T>) {
+        std::mem::swap(&mut self.draw_data, &mut other.draw_data);
+    }
+
+    /// Draw a pixel of the target, if it is inside of it and of the clipping rectangle
+    fn draw_pixel(&mut self, pos: Vi2d, col: Color) {
+        let pixel_mode = self.draw_data.pixel_mode;
+        let blend_factor = self.draw_data.blend_factor;
+        let Vi2d { x, y } = pos;
+        if x >= self.sprite.size().x.try_into().unwrap()
+            || y >= self.sprite.size().y.try_into().unwrap()
+            || x < 0
+            || y < 0
+        {
+            return;
+        }
+        if let Some((min, max)) = self.draw_data.clips.last() {
+            if x < min.x || y < min.y || x >= max.x || y >= max.y {
+                return;
+            }
+        }
+        let pos = pos.cast_u32();
+        let col = match pixel_mode {
+            // Those don't need to read the current pixel
+            PixelMode::Normal | PixelMode::Mask => {
+                pixel_mode.blend(pos, col, Color::BLANK, blend_factor)
+            }
+            _ => {
+                let current_color: Color = unsafe { self.sprite.get_pixel_unchecked(pos) };
+                pixel_mode.blend(pos, col, current_color, blend_factor)
+            }
+        };
+        if let Some(col) = col {
+            unsafe {
+                self.sprite.set_pixel_unchecked(pos, col);
+            }
+        }
+    }
 }
 
 impl DrawData {
@@ -753,6 +1038,8 @@ impl DrawData {
         Self {
             pixel_mode: PixelMode::Normal,
             blend_factor: 1.0f32,
+            clips: Vec::new(),
+            transforms: Vec::new(),
         }
     }
 }
@@ -778,7 +1065,7 @@ fn create_text() -> &'static Sprite {
                 let r: u32 = sym1 << 18 | sym2 << 12 | sym3 << 6 | sym4;
                 for i in 0..24 {
                     let k = if (r & (1 << i)) == 0 { 0 } else { 255 };
-                    sheet.set_pixel(px, py, [k, k, k].into());
+                    sheet.set_pixel(px, py, Color::new_with_alpha(k, k, k, k));
                     py += 1;
                     if py == 48 {
                         px += 1;
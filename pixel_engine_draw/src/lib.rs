@@ -6,4 +6,5 @@ extern crate slab;
 
 pub mod graphics;
 pub mod traits;
+pub mod transform;
 pub mod vector2;
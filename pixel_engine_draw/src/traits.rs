@@ -1,5 +1,6 @@
 use super::graphics::{Color, PixelMode, Sprite};
-use super::vector2::{Vi2d, Vu2d};
+use super::transform::{Sampling, Transform2D};
+use super::vector2::{Vf2d, Vi2d, Vu2d};
 
 use crate::graphics::DrawSpriteTrait;
 
@@ -14,13 +15,39 @@ macro_rules! impl_trait {
 pub trait SmartDrawingTrait: DrawSpriteTrait {
     /// Get the size of the target
     fn get_size(&self) -> Vu2d;
-    /// Get The textsheet (A [`Sprite`])
+    /// Get The textsheet (A [`Sprite`]), the glyphs are white on a transparent background
     fn get_textsheet(&self) -> &'static Sprite;
-    /// Clear the Sprite With the given [`Color`]
+    /// Clear the Sprite With the given [`Color`], the whole target is cleared whatever the
+    /// clipping rectangle and transform
     fn clear(&mut self, col: Color);
     /// Set the pixel data at the given coordinates to the given Color
-    /// Will use the current [`PixelMode`]
+    /// Will use the current [`PixelMode`], transform and clipping rectangle
     fn draw<P: Into<Vi2d>>(&mut self, pos: P, col: Color);
+    /// Only draw inside of the rectangle with the top left corner at `pos` and the given `size`,
+    /// until [`SmartDrawingTrait::pop_clip`] is called.
+    /// The rectangle goes through the current transform and is kept inside of the previous
+    /// clipping rectangle, so clips can be nested.
+    ///
+    /// Does nothing by default, for the targets that don't support clipping
+    fn push_clip<P: Into<Vi2d>>(&mut self, _pos: P, _size: P) {}
+    /// Go back to the previous clipping rectangle
+    fn pop_clip(&mut self) {}
+    /// Move everything drawn by `offset` and scale it by `scale`, until
+    /// [`SmartDrawingTrait::pop_transform`] is called. Transforms are combined with the previous
+    /// ones, so things can be drawn in local coordinates: `(0, 0)` becomes `offset`.
+    ///
+    /// Does nothing by default, for the targets that don't support transforms
+    fn push_transform<P: Into<Vi2d>>(&mut self, _offset: P, _scale: f32) {}
+    /// Go back to the previous transform
+    fn pop_transform(&mut self) {}
+    /// The area that can be drawn on, in the coordinates of the current transform: the top left
+    /// (inclusive) and bottom right (exclusive) corners of the clipping rectangle, or of the whole
+    /// target without one.
+    ///
+    /// The whole target by default
+    fn get_clip(&self) -> (Vi2d, Vi2d) {
+        (Vi2d { x: 0, y: 0 }, self.get_size().cast_i32())
+    }
     /// Get the Pixel Data at the given coordinates
     fn get_pixel<P: Into<Vi2d>>(&self, pos: P) -> Option<Color>;
     /// Return the [`PixelMode`]
@@ -654,6 +681,57 @@ pub trait SpriteTrait: SmartDrawingTrait {
             }
         }
     }
+
+    /// Draw a [`Sprite`] moved, rotated, scaled or sheared by `transform`, which maps the
+    /// positions of the sprite to the positions of the target.
+    ///
+    /// Each pixel of the target covered by the sprite gets the color read at its center, through
+    /// the inverse of the transform, with `sampling`. Nothing is drawn if the transform can't be
+    /// inverted
+    fn draw_sprite_transformed(
+        &mut self,
+        sprite: &Sprite,
+        transform: &Transform2D,
+        sampling: Sampling,
+    ) {
+        #![allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+        let Some(inverse) = transform.invert() else {
+            return;
+        };
+        let (width, height) = (sprite.width() as f32, sprite.height() as f32);
+        let corners = [(0.0, 0.0), (width, 0.0), (0.0, height), (width, height)]
+            .map(|corner| transform.forward(corner));
+        let (mut min, mut max) = (corners[0], corners[0]);
+        for corner in &corners[1..] {
+            min = Vf2d {
+                x: min.x.min(corner.x),
+                y: min.y.min(corner.y),
+            };
+            max = Vf2d {
+                x: max.x.max(corner.x),
+                y: max.y.max(corner.y),
+            };
+        }
+        // Only the pixels that can be drawn are sampled
+        let (clip_min, clip_max) = self.get_clip();
+        let (x0, x1) = (
+            (min.x.floor() as i32).max(clip_min.x),
+            (max.x.ceil() as i32).min(clip_max.x),
+        );
+        let (y0, y1) = (
+            (min.y.floor() as i32).max(clip_min.y),
+            (max.y.ceil() as i32).min(clip_max.y),
+        );
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let pos = inverse.forward((x as f32 + 0.5, y as f32 + 0.5));
+                if pos.x < 0.0 || pos.y < 0.0 || pos.x >= width || pos.y >= height {
+                    continue;
+                }
+                self.draw((x, y), sampling.sample(sprite, pos));
+            }
+        }
+    }
 }
 
 impl_trait!(SpriteTrait);
@@ -0,0 +1,179 @@
+//! Affine transforms, to draw rotated, scaled or sheared sprites with
+//! [`SpriteTrait::draw_sprite_transformed`](crate::traits::SpriteTrait::draw_sprite_transformed)
+
+use crate::graphics::{Color, Sprite};
+use crate::vector2::Vf2d;
+
+/// A 2D affine transform, it maps the positions of a sprite to positions of the target.
+///
+/// It is built by chaining the operations, each one is applied after the previous ones:
+/// ```
+/// # use pixel_engine_draw::transform::Transform2D;
+/// // Rotate the sprite around its center, then put that center at (32, 32)
+/// let transform = Transform2D::new()
+///     .translate(-8.0, -8.0)
+///     .rotate(std::f32::consts::FRAC_PI_2)
+///     .translate(32.0, 32.0);
+/// let corner = transform.forward((16.0, 16.0));
+/// assert!((corner.x - 24.0).abs() < 1e-4 && (corner.y - 40.0).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2D {
+    /// The first two rows of the 3x3 matrix, the last one is always `[0, 0, 1]`
+    matrix: [[f32; 3]; 2],
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform2D {
+    /// The transform that doesn't move anything
+    pub const IDENTITY: Transform2D = Transform2D {
+        matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+    };
+
+    /// Create an identity transform
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::IDENTITY
+    }
+
+    /// Move by `(x, y)`
+    #[must_use]
+    pub fn translate(self, x: f32, y: f32) -> Self {
+        self.then(&Transform2D {
+            matrix: [[1.0, 0.0, x], [0.0, 1.0, y]],
+        })
+    }
+
+    /// Rotate by `theta` radians around the origin, clockwise as the y axis goes down
+    #[must_use]
+    pub fn rotate(self, theta: f32) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        self.then(&Transform2D {
+            matrix: [[cos, -sin, 0.0], [sin, cos, 0.0]],
+        })
+    }
+
+    /// Stretch horizontally by `x` and vertically by `y`, from the origin
+    #[must_use]
+    pub fn scale(self, x: f32, y: f32) -> Self {
+        self.then(&Transform2D {
+            matrix: [[x, 0.0, 0.0], [0.0, y, 0.0]],
+        })
+    }
+
+    /// Shear, the x coordinates move by `x` times the y coordinate and the y coordinates by `y`
+    /// times the x coordinate
+    #[must_use]
+    pub fn shear(self, x: f32, y: f32) -> Self {
+        self.then(&Transform2D {
+            matrix: [[1.0, x, 0.0], [y, 1.0, 0.0]],
+        })
+    }
+
+    /// Compose the two transforms: `other` is applied after `self`
+    #[must_use]
+    pub fn then(&self, other: &Transform2D) -> Self {
+        let (a, b) = (&other.matrix, &self.matrix);
+        let mut matrix = [[0.0; 3]; 2];
+        for (row, a_row) in matrix.iter_mut().zip(a) {
+            for (col, value) in row.iter_mut().enumerate() {
+                *value = a_row[0] * b[0][col] + a_row[1] * b[1][col];
+            }
+            row[2] += a_row[2];
+        }
+        Transform2D { matrix }
+    }
+
+    /// The transform that undoes this one, `None` if it squashes everything on a line or a point
+    #[must_use]
+    pub fn invert(&self) -> Option<Self> {
+        let [[xx, xy, tx], [yx, yy, ty]] = self.matrix;
+        let determinant = xx * yy - xy * yx;
+        if determinant.abs() < f32::EPSILON {
+            return None;
+        }
+        let inverse = 1.0 / determinant;
+        Some(Transform2D {
+            matrix: [
+                [yy * inverse, -xy * inverse, (xy * ty - tx * yy) * inverse],
+                [-yx * inverse, xx * inverse, (tx * yx - xx * ty) * inverse],
+            ],
+        })
+    }
+
+    /// Apply the transform to a position
+    #[must_use]
+    pub fn forward<P: Into<Vf2d>>(&self, pos: P) -> Vf2d {
+        let Vf2d { x, y } = pos.into();
+        let [[xx, xy, tx], [yx, yy, ty]] = self.matrix;
+        Vf2d {
+            x: xx * x + xy * y + tx,
+            y: yx * x + yy * y + ty,
+        }
+    }
+}
+
+/// How the color of a sprite is read between its pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sampling {
+    /// The pixel the position is on, it keeps the pixels sharp
+    Nearest,
+    /// The four closest pixels, weighted by their distance, it smooths the result
+    Bilinear,
+}
+
+impl Sampling {
+    /// Read the color of the sprite at `pos`, in pixels. The positions outside of the sprite are
+    /// clamped to its edges, an empty sprite is [`Color::BLANK`] everywhere
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn sample(self, sprite: &Sprite, pos: Vf2d) -> Color {
+        if sprite.width() == 0 || sprite.height() == 0 {
+            return Color::BLANK;
+        }
+        let max = (sprite.width() - 1, sprite.height() - 1);
+        let pixel = |x: f32, y: f32| {
+            sprite.get_pixel(
+                (x.max(0.0) as u32).min(max.0),
+                (y.max(0.0) as u32).min(max.1),
+            )
+        };
+        match self {
+            Sampling::Nearest => pixel(pos.x.floor(), pos.y.floor()),
+            Sampling::Bilinear => {
+                // The centers of the pixels are at the half
+                let (x, y) = (pos.x - 0.5, pos.y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                let texels = [
+                    (pixel(x0, y0), (1.0 - tx) * (1.0 - ty)),
+                    (pixel(x0 + 1.0, y0), tx * (1.0 - ty)),
+                    (pixel(x0, y0 + 1.0), (1.0 - tx) * ty),
+                    (pixel(x0 + 1.0, y0 + 1.0), tx * ty),
+                ];
+                let channel = |get: fn(Color) -> u8| {
+                    let value: f32 = texels
+                        .iter()
+                        .map(|&(col, weight)| f32::from(get(col)) * weight)
+                        .sum();
+                    value.round() as u8
+                };
+                Color::new_with_alpha(
+                    channel(|c| c.r),
+                    channel(|c| c.g),
+                    channel(|c| c.b),
+                    channel(|c| c.a),
+                )
+            }
+        }
+    }
+}